@@ -20,7 +20,8 @@ const browserMap = {
   'chrome': Browser.CHROME,
   'chromium': Browser.CHROMIUM,
   'firefox': Browser.FIREFOX,
-  'chromedriver': Browser.CHROMEDRIVER
+  'chromedriver': Browser.CHROMEDRIVER,
+  'chrome-headless-shell': Browser.CHROMEHEADLESSSHELL
 };
 
 // 获取应用数据目录
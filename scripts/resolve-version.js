@@ -0,0 +1,65 @@
+#!/usr/bin/env node
+
+import { Browser, BrowserPlatform, resolveBuildId } from '@puppeteer/browsers';
+
+// 从命令行参数获取配置
+const args = process.argv.slice(2);
+const config = {};
+
+// 解析命令行参数
+for (let i = 0; i < args.length; i += 2) {
+  const key = args[i].replace('--', '');
+  const value = args[i + 1];
+  config[key] = value;
+}
+
+// 必需参数验证
+if (!config.browser || !config.version || !config.platform) {
+  console.error('ERROR:Missing required parameters');
+  console.error('Usage: node resolve-version.js --browser chrome --version stable --platform win64');
+  process.exit(1);
+}
+
+// 浏览器类型映射
+const browserMap = {
+  'chrome': Browser.CHROME,
+  'chromium': Browser.CHROMIUM,
+  'firefox': Browser.FIREFOX,
+  'chromedriver': Browser.CHROMEDRIVER,
+  'chrome-headless-shell': Browser.CHROMEHEADLESSSHELL
+};
+
+// 平台映射
+const platformMap = {
+  'win32': BrowserPlatform.WIN32,
+  'win64': BrowserPlatform.WIN64,
+  'mac': BrowserPlatform.MAC,
+  'mac_x64': BrowserPlatform.MAC,
+  'mac_arm': BrowserPlatform.MAC_ARM,
+  'linux': BrowserPlatform.LINUX,
+  'linux64': BrowserPlatform.LINUX,
+  'linux_arm': BrowserPlatform.LINUX_ARM
+};
+
+const browser = browserMap[config.browser.toLowerCase()];
+const platform = platformMap[config.platform.toLowerCase()];
+
+if (!browser || !platform) {
+  console.error(`ERROR:Unsupported browser or platform: ${config.browser} ${config.platform}`);
+  process.exit(1);
+}
+
+// 与 download-browser.js 相同的解析方式，无法解析时原样作为 build ID
+async function resolveVersion() {
+  let buildId;
+  try {
+    buildId = await resolveBuildId(browser, platform, config.version);
+  } catch {
+    buildId = config.version;
+  }
+
+  console.log(JSON.stringify({ buildId }));
+  process.exit(0);
+}
+
+resolveVersion();
@@ -27,7 +27,8 @@ const browserMap = {
   'chrome': Browser.CHROME,
   'chromium': Browser.CHROMIUM,
   'firefox': Browser.FIREFOX,
-  'chromedriver': Browser.CHROMEDRIVER
+  'chromedriver': Browser.CHROMEDRIVER,
+  'chrome-headless-shell': Browser.CHROMEHEADLESSSHELL
 };
 
 // 平台映射
@@ -37,7 +38,8 @@ const platformMap = {
   'mac': BrowserPlatform.MAC,
   'mac_arm': BrowserPlatform.MAC_ARM,
   'linux': BrowserPlatform.LINUX,
-  'linux64': BrowserPlatform.LINUX
+  'linux64': BrowserPlatform.LINUX,
+  'linux_arm': BrowserPlatform.LINUX_ARM
 };
 
 const browser = browserMap[config.browser.toLowerCase()];
@@ -1,5 +1,12 @@
-use crate::models::{BrowserInfo, DownloadTask, SystemInfo, BrowserType, BrowserLaunchConfig, LaunchParameter, ParameterTemplate, TemplateCategory};
-use crate::services::{AppState, parameter_manager::SecurityValidation};
+use crate::models::{BrowserFamily, BrowserInfo, BundleImportReport, BundleManifest, DiscoveredBrowser, DownloadTask, InstallOutcome, Platform, SystemInfo, BrowserType, BrowserLaunchConfig, ConfigConflictStrategy, LaunchConfigImportReport, LaunchParameter, ParameterTemplate, TemplateCategory};
+use crate::models::{BrowserDeleteOptions, ClearBrowsersReport, TrashEntry, TrashPurgeReport};
+use crate::models::{RepairOptions, RepairReport, RetentionPolicy, RetentionReport, StorageUsage};
+use crate::models::{BrowserLaunch, CaptureRequest, CaptureResult, VisualDiffOptions, VisualDiffResult, VisualDiffSource, ConfigLayer, LaunchArgs, LaunchLogStream, LaunchMatrix, LaunchMatrixInstance, LaunchMatrixRequest, LaunchOptions, LaunchRecord, LaunchVariableInfo, LaunchArgsExplanation, LayerState, SwitchCatalog};
+use crate::models::{PolicyCatalog, PolicySet};
+use crate::services::{AppState, flag_validator::FlagValidationReport, policy_manager::PolicyValidationReport};
+use crate::services::security_policy::{SecurityPolicy, SecurityValidation};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
 use tauri::State;
 
 // 浏览器管理相关命令
@@ -13,30 +20,71 @@ pub async fn download_browser(
     state: State<'_, AppState>,
     browser_type: String,
     version: String,
-    platform: String,
-) -> Result<String, String> {
-    let browser_type_enum = match browser_type.as_str() {
-        "chrome" => BrowserType::Chrome,
-        "chromium" => BrowserType::Chromium,
-        "firefox" => BrowserType::Firefox,
-        "chromedriver" => BrowserType::ChromeDriver,
-        _ => return Err("Invalid browser type".to_string()),
-    };
+    platform: Platform,
+) -> Result<InstallOutcome, String> {
+    let browser_type_enum: BrowserType = browser_type.parse()?;
+
+    state
+        .browser_manager
+        .install_browser(browser_type_enum, &version, platform)
+        .await
+}
+
+#[tauri::command]
+pub async fn import_browser_archive(
+    state: State<'_, AppState>,
+    source_path: String,
+) -> Result<BrowserInfo, String> {
+    state
+        .browser_manager
+        .import_browser_archive(std::path::Path::new(&source_path))
+        .await
+}
+
+#[tauri::command]
+pub async fn discover_system_browsers(
+    state: State<'_, AppState>,
+) -> Result<Vec<DiscoveredBrowser>, String> {
+    state.browser_manager.discover_system_browsers().await
+}
 
+#[tauri::command]
+pub async fn register_system_browser(
+    state: State<'_, AppState>,
+    executable_path: String,
+) -> Result<BrowserInfo, String> {
     state
         .browser_manager
-        .install_browser(browser_type_enum, &version, &platform)
+        .register_system_browser(std::path::Path::new(&executable_path))
         .await
 }
 
 #[tauri::command]
-pub async fn delete_browser(state: State<'_, AppState>, browser_id: String) -> Result<(), String> {
-    state.browser_manager.delete_browser(&browser_id).await
+pub async fn unregister_browser(state: State<'_, AppState>, browser_id: String) -> Result<(), String> {
+    state.browser_manager.unregister_browser(&browser_id).await
 }
 
 #[tauri::command]
-pub async fn clear_all_browsers(state: State<'_, AppState>) -> Result<(), String> {
-    state.browser_manager.clear_all_browsers().await
+pub async fn delete_browser(
+    state: State<'_, AppState>,
+    browser_id: String,
+    options: Option<BrowserDeleteOptions>,
+) -> Result<(), String> {
+    state
+        .browser_manager
+        .delete_browser(&browser_id, options.unwrap_or_default())
+        .await
+}
+
+#[tauri::command]
+pub async fn clear_all_browsers(
+    state: State<'_, AppState>,
+    stop_running: Option<bool>,
+) -> Result<ClearBrowsersReport, String> {
+    state
+        .browser_manager
+        .clear_all_browsers(stop_running.unwrap_or(false))
+        .await
 }
 
 #[tauri::command]
@@ -44,24 +92,290 @@ pub async fn open_browser(
     state: State<'_, AppState>,
     browser_id: String,
     args: Option<Vec<String>>,
-) -> Result<(), String> {
-    // 首先获取参数管理器中的默认启动参数
-    let parameter_args = state
+    acknowledge_risks: Option<bool>,
+) -> Result<BrowserLaunch, String> {
+    // 合并参数管理器中的默认启动参数和用户指定的参数（用户参数优先）
+    let resolved = state
         .parameter_manager
-        .build_launch_args(&browser_id, None)
+        .resolve_launch_args(&browser_id, None, args)
+        .await?;
+    let options = LaunchOptions {
+        args: resolved.args,
+        env: resolved.env,
+        working_dir: resolved.working_dir,
+        acknowledged: acknowledge_risks.unwrap_or(false),
+        config_ids: resolved.config_ids,
+        relaunch_of: None,
+        firefox_prefs: resolved.firefox_prefs,
+        profile_seed: resolved.profile_seed,
+        policies: resolved.policies,
+    };
+
+    state
+        .browser_manager
+        .launch_browser(&browser_id, options)
         .await
-        .unwrap_or_default();
+}
 
-    // 合并参数管理器的参数和用户指定的参数
-    let combined_args = if let Some(user_args) = args {
-        parameter_args.into_iter().chain(user_args.into_iter()).collect()
-    } else {
-        parameter_args
+/// 同时启动多个浏览器版本，每个实例使用独立的用户数据目录和调试端口
+#[tauri::command]
+pub async fn launch_matrix(
+    state: State<'_, AppState>,
+    request: LaunchMatrixRequest,
+) -> Result<LaunchMatrix, String> {
+    if request.browser_ids.is_empty() {
+        return Err("No browsers specified for launch matrix".to_string());
+    }
+
+    let matrix_id = uuid::Uuid::new_v4().to_string();
+    let profiles_root = crate::utils::get_profiles_dir()?.join(format!("matrix-{}", matrix_id));
+    let count = request.browser_ids.len();
+    let mut used_ports = HashSet::new();
+    let mut instances = Vec::with_capacity(count);
+
+    for (index, browser_id) in request.browser_ids.iter().enumerate() {
+        let profile_dir = profiles_root.join(format!("{}-{}", index, browser_id));
+        let mut instance = LaunchMatrixInstance {
+            browser_id: browser_id.clone(),
+            launch: None,
+            debugging_port: None,
+            profile_dir: Some(profile_dir.to_string_lossy().to_string()),
+            window: request.tiling.as_ref().map(|t| t.bounds(index, count)),
+            error: None,
+        };
+
+        let port = find_distinct_port(&mut used_ports);
+        let result = match port {
+            Ok(port) => {
+                instance.debugging_port = Some(port);
+                launch_matrix_instance(&state, &request, &instance, &profile_dir, port).await
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(launch) => instance.launch = Some(launch),
+            Err(e) => {
+                tracing::warn!("Launch matrix {}: failed to launch {}: {}", matrix_id, browser_id, e);
+                instance.error = Some(e);
+            }
+        }
+        instances.push(instance);
+    }
+
+    Ok(LaunchMatrix { matrix_id, instances })
+}
+
+async fn launch_matrix_instance(
+    state: &AppState,
+    request: &LaunchMatrixRequest,
+    instance: &LaunchMatrixInstance,
+    profile_dir: &Path,
+    port: u16,
+) -> Result<BrowserLaunch, String> {
+    crate::utils::ensure_dir_exists(profile_dir).await?;
+
+    let family = state
+        .browser_manager
+        .get_browser_info(&instance.browser_id)
+        .await?
+        .browser_type
+        .family();
+    // 隔离所需的参数在参数层被禁用时也必须生效
+    let mut required_args = vec![
+        format!("{}={}", family.profile_switch(), profile_dir.to_string_lossy()),
+        format!("--remote-debugging-port={}", port),
+    ];
+    // Firefox 不支持通过命令行指定窗口位置
+    if let (Some(window), BrowserFamily::Chromium) = (&instance.window, family) {
+        required_args.extend(window.to_args());
+    }
+    required_args.extend(request.urls.iter().cloned());
+
+    let resolved = state
+        .parameter_manager
+        .resolve_launch_args_with(
+            &instance.browser_id,
+            request.config_id.clone().map(|id| vec![id]),
+            None,
+            &required_args,
+        )
+        .await?;
+    let options = LaunchOptions {
+        args: resolved.args,
+        env: resolved.env,
+        working_dir: resolved.working_dir,
+        acknowledged: request.acknowledged,
+        config_ids: resolved.config_ids,
+        relaunch_of: None,
+        firefox_prefs: resolved.firefox_prefs,
+        profile_seed: resolved.profile_seed,
+        policies: resolved.policies,
     };
 
     state
         .browser_manager
-        .launch_browser(&browser_id, Some(combined_args))
+        .launch_browser(&instance.browser_id, options)
+        .await
+}
+
+/// 分配一个本次并排启动中尚未使用的端口
+fn find_distinct_port(used_ports: &mut HashSet<u16>) -> Result<u16, String> {
+    for _ in 0..10 {
+        let port = crate::utils::find_free_port()?;
+        if used_ports.insert(port) {
+            return Ok(port);
+        }
+    }
+    Err("Failed to allocate a distinct debugging port".to_string())
+}
+
+/// 停止多个浏览器进程，返回实际被停止的启动ID
+#[tauri::command]
+pub async fn stop_launches(
+    state: State<'_, AppState>,
+    launch_ids: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let mut stopped = Vec::new();
+    for launch_id in launch_ids {
+        if state.browser_manager.stop_launch(&launch_id).await? {
+            stopped.push(launch_id);
+        }
+    }
+    Ok(stopped)
+}
+
+#[tauri::command]
+pub async fn capture_page(
+    state: State<'_, AppState>,
+    request: CaptureRequest,
+) -> Result<CaptureResult, String> {
+    state.capture_manager.capture_page(request).await
+}
+
+#[tauri::command]
+pub async fn compare_screenshots(
+    state: State<'_, AppState>,
+    baseline: VisualDiffSource,
+    candidate: VisualDiffSource,
+    options: Option<VisualDiffOptions>,
+) -> Result<VisualDiffResult, String> {
+    state
+        .capture_manager
+        .compare_screenshots(baseline, candidate, options.unwrap_or_default())
+        .await
+}
+
+#[tauri::command]
+pub async fn save_visual_baseline(
+    state: State<'_, AppState>,
+    name: String,
+    source: VisualDiffSource,
+) -> Result<String, String> {
+    state.capture_manager.save_baseline(&name, source).await
+}
+
+// 存储空间管理相关命令
+#[tauri::command]
+pub async fn get_storage_usage(state: State<'_, AppState>) -> Result<StorageUsage, String> {
+    state.storage_manager.get_storage_usage().await
+}
+
+#[tauri::command]
+pub async fn get_retention_policy(state: State<'_, AppState>) -> Result<RetentionPolicy, String> {
+    state.storage_manager.get_retention_policy().await
+}
+
+#[tauri::command]
+pub async fn set_retention_policy(
+    state: State<'_, AppState>,
+    policy: RetentionPolicy,
+) -> Result<(), String> {
+    state.storage_manager.set_retention_policy(policy).await
+}
+
+/// 默认只预览将被删除的版本
+#[tauri::command]
+pub async fn apply_retention_policy(
+    state: State<'_, AppState>,
+    dry_run: Option<bool>,
+) -> Result<RetentionReport, String> {
+    state
+        .storage_manager
+        .apply_retention_policy(dry_run.unwrap_or(true))
+        .await
+}
+
+#[tauri::command]
+pub async fn list_browser_trash(state: State<'_, AppState>) -> Result<Vec<TrashEntry>, String> {
+    state.browser_manager.list_trash().await
+}
+
+#[tauri::command]
+pub async fn restore_browser(
+    state: State<'_, AppState>,
+    trash_id: String,
+) -> Result<BrowserInfo, String> {
+    state.browser_manager.restore_from_trash(&trash_id).await
+}
+
+/// 未指定天数时清空回收站
+#[tauri::command]
+pub async fn purge_browser_trash(
+    state: State<'_, AppState>,
+    older_than_days: Option<u32>,
+) -> Result<TrashPurgeReport, String> {
+    state
+        .browser_manager
+        .purge_trash(older_than_days.map(|days| chrono::Duration::days(days as i64)))
+        .await
+}
+
+/// 核对数据库与浏览器目录，未指定选项时只修复失效记录和可执行文件路径
+#[tauri::command]
+pub async fn scan_and_repair(
+    state: State<'_, AppState>,
+    options: Option<RepairOptions>,
+) -> Result<RepairReport, String> {
+    state
+        .storage_manager
+        .scan_and_repair(options.unwrap_or_default())
+        .await
+}
+
+#[tauri::command]
+pub async fn list_launch_history(
+    state: State<'_, AppState>,
+    browser_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<LaunchRecord>, String> {
+    state
+        .browser_manager
+        .list_launch_history(browser_id.as_deref(), limit.unwrap_or(100))
+        .await
+}
+
+#[tauri::command]
+pub async fn relaunch(
+    state: State<'_, AppState>,
+    launch_id: String,
+    acknowledge_risks: Option<bool>,
+) -> Result<BrowserLaunch, String> {
+    state
+        .browser_manager
+        .relaunch(&launch_id, acknowledge_risks.unwrap_or(false))
+        .await
+}
+
+#[tauri::command]
+pub async fn tail_launch_log(
+    state: State<'_, AppState>,
+    launch_id: String,
+    stream: LaunchLogStream,
+    lines: Option<usize>,
+) -> Result<String, String> {
+    state
+        .browser_manager
+        .read_launch_log(&launch_id, stream, lines)
         .await
 }
 
@@ -73,6 +387,30 @@ pub async fn get_browser_info(
     state.browser_manager.get_browser_info(&browser_id).await
 }
 
+// 浏览器安装包相关命令
+#[tauri::command]
+pub async fn export_browser_bundle(
+    state: State<'_, AppState>,
+    browser_ids: Vec<String>,
+    output_path: String,
+) -> Result<BundleManifest, String> {
+    state
+        .bundle_manager
+        .export_bundle(&browser_ids, std::path::Path::new(&output_path))
+        .await
+}
+
+#[tauri::command]
+pub async fn import_browser_bundle(
+    state: State<'_, AppState>,
+    bundle_path: String,
+) -> Result<BundleImportReport, String> {
+    state
+        .bundle_manager
+        .import_bundle(std::path::Path::new(&bundle_path))
+        .await
+}
+
 // 下载管理相关命令
 #[tauri::command]
 pub async fn get_download_progress(
@@ -122,26 +460,13 @@ pub async fn get_available_versions(
 
 #[tauri::command]
 pub async fn get_system_info() -> Result<SystemInfo, String> {
-    let platform = if cfg!(target_os = "windows") {
-        "win64".to_string()
-    } else if cfg!(target_os = "macos") {
-        if cfg!(target_arch = "aarch64") {
-            "mac_arm".to_string()
-        } else {
-            "mac_x64".to_string()
-        }
-    } else if cfg!(target_os = "linux") {
-        "linux64".to_string()
-    } else {
-        "unknown".to_string()
-    };
-
     let arch = std::env::consts::ARCH.to_string();
 
     Ok(SystemInfo {
-        platform,
+        platform: Platform::current(),
         arch,
         available_versions: vec![], // 可以在这里填充可用版本
+        supported_platforms: Platform::ALL.to_vec(),
     })
 }
 
@@ -244,19 +569,59 @@ pub async fn set_default_launch_config(
     state.parameter_manager.set_as_default(&config_id).await
 }
 
+/// 获取启动参数模板，指定浏览器类型时只返回适用于该类型的模板
 #[tauri::command]
-pub async fn get_launch_templates() -> Result<Vec<ParameterTemplate>, String> {
-    Ok(ParameterTemplate::get_builtin_templates())
+pub async fn get_launch_templates(
+    state: State<'_, AppState>,
+    browser_type: Option<String>,
+) -> Result<Vec<ParameterTemplate>, String> {
+    match browser_type {
+        Some(browser_type) => {
+            let browser_type: BrowserType = browser_type.parse()?;
+            state
+                .parameter_manager
+                .get_templates_for_browser_type(&browser_type)
+                .await
+        }
+        None => state.parameter_manager.get_all_templates().await,
+    }
 }
 
 #[tauri::command]
 pub async fn get_launch_templates_by_category(
+    state: State<'_, AppState>,
     category: TemplateCategory,
 ) -> Result<Vec<ParameterTemplate>, String> {
-    Ok(ParameterTemplate::get_builtin_templates()
-        .into_iter()
-        .filter(|t| t.category == category)
-        .collect())
+    state.parameter_manager.get_templates_by_category(category).await
+}
+
+#[tauri::command]
+pub async fn create_template_from_config(
+    state: State<'_, AppState>,
+    config_id: String,
+    name: String,
+    description: String,
+    category: TemplateCategory,
+) -> Result<ParameterTemplate, String> {
+    state.parameter_manager
+        .create_template_from_config(&config_id, name, description, category)
+        .await
+}
+
+#[tauri::command]
+pub async fn update_launch_template(
+    state: State<'_, AppState>,
+    template: ParameterTemplate,
+) -> Result<(), String> {
+    state.parameter_manager.update_template(template).await
+}
+
+#[tauri::command]
+pub async fn delete_launch_template(
+    state: State<'_, AppState>,
+    template_id: String,
+) -> Result<(), String> {
+    state.parameter_manager.delete_template(&template_id).await
 }
 
 #[tauri::command]
@@ -270,6 +635,53 @@ pub async fn build_browser_launch_args(
         .await
 }
 
+#[tauri::command]
+pub async fn resolve_browser_launch_args(
+    state: State<'_, AppState>,
+    browser_id: String,
+    config_ids: Option<Vec<String>>,
+    extra_args: Option<Vec<String>>,
+) -> Result<LaunchArgs, String> {
+    state.parameter_manager
+        .resolve_launch_args(&browser_id, config_ids, extra_args)
+        .await
+}
+
+#[tauri::command]
+pub async fn explain_launch_args(
+    state: State<'_, AppState>,
+    browser_id: String,
+    config_ids: Option<Vec<String>>,
+    extra_args: Option<Vec<String>>,
+) -> Result<LaunchArgsExplanation, String> {
+    state.parameter_manager
+        .explain_launch_args(&browser_id, config_ids, extra_args)
+        .await
+}
+
+#[tauri::command]
+pub async fn get_builtin_launch_variables(
+    state: State<'_, AppState>,
+) -> Result<Vec<LaunchVariableInfo>, String> {
+    Ok(state.parameter_manager.builtin_variable_info())
+}
+
+#[tauri::command]
+pub async fn get_launch_layer_states(
+    state: State<'_, AppState>,
+) -> Result<Vec<LayerState>, String> {
+    state.parameter_manager.get_layer_states().await
+}
+
+#[tauri::command]
+pub async fn set_launch_layer_enabled(
+    state: State<'_, AppState>,
+    layer: ConfigLayer,
+    enabled: bool,
+) -> Result<(), String> {
+    state.parameter_manager.set_layer_enabled(layer, enabled).await
+}
+
 #[tauri::command]
 pub async fn validate_config_security(
     state: State<'_, AppState>,
@@ -278,6 +690,97 @@ pub async fn validate_config_security(
     state.parameter_manager.validate_config_security(&config_id).await
 }
 
+#[tauri::command]
+pub async fn check_launch_security(
+    state: State<'_, AppState>,
+    browser_id: String,
+    args: Option<Vec<String>>,
+) -> Result<SecurityValidation, String> {
+    let resolved = state
+        .parameter_manager
+        .resolve_launch_args(&browser_id, None, args)
+        .await?;
+    Ok(state.security_policy.evaluate(&resolved.args))
+}
+
+#[tauri::command]
+pub async fn get_security_policy(state: State<'_, AppState>) -> Result<SecurityPolicy, String> {
+    Ok(state.security_policy.policy())
+}
+
+#[tauri::command]
+pub async fn reload_security_policy(state: State<'_, AppState>) -> Result<SecurityPolicy, String> {
+    Ok(state.security_policy.reload())
+}
+
+#[tauri::command]
+pub async fn validate_launch_config_flags(
+    state: State<'_, AppState>,
+    config_id: String,
+    browser_id: Option<String>,
+) -> Result<FlagValidationReport, String> {
+    state.parameter_manager
+        .validate_config_flags(&config_id, browser_id.as_deref())
+        .await
+}
+
+#[tauri::command]
+pub async fn get_chrome_switch_catalog() -> Result<SwitchCatalog, String> {
+    Ok(SwitchCatalog::builtin().clone())
+}
+
+#[tauri::command]
+pub async fn get_chrome_policy_catalog(
+    state: State<'_, AppState>,
+) -> Result<PolicyCatalog, String> {
+    Ok(state.policy_manager.catalog().clone())
+}
+
+#[tauri::command]
+pub async fn get_policy_sets(
+    state: State<'_, AppState>,
+) -> Result<Vec<PolicySet>, String> {
+    state.policy_manager.get_policy_sets().await
+}
+
+#[tauri::command]
+pub async fn create_policy_set(
+    state: State<'_, AppState>,
+    name: String,
+    description: Option<String>,
+    policies: BTreeMap<String, serde_json::Value>,
+) -> Result<PolicySet, String> {
+    state
+        .policy_manager
+        .create_policy_set(name, description.unwrap_or_default(), policies)
+        .await
+}
+
+#[tauri::command]
+pub async fn update_policy_set(
+    state: State<'_, AppState>,
+    policy_set: PolicySet,
+) -> Result<PolicySet, String> {
+    state.policy_manager.update_policy_set(policy_set).await
+}
+
+#[tauri::command]
+pub async fn delete_policy_set(
+    state: State<'_, AppState>,
+    policy_set_id: String,
+) -> Result<(), String> {
+    state.policy_manager.delete_policy_set(&policy_set_id).await
+}
+
+/// 根据策略目录校验策略（未知策略为警告，类型和取值错误为错误）
+#[tauri::command]
+pub async fn validate_policies(
+    state: State<'_, AppState>,
+    policies: BTreeMap<String, serde_json::Value>,
+) -> Result<PolicyValidationReport, String> {
+    Ok(state.policy_manager.validate_policies(&policies))
+}
+
 #[tauri::command]
 pub async fn update_config_parameters(
     state: State<'_, AppState>,
@@ -289,6 +792,29 @@ pub async fn update_config_parameters(
         .await
 }
 
+#[tauri::command]
+pub async fn export_launch_configs(
+    state: State<'_, AppState>,
+    config_ids: Option<Vec<String>>,
+    output_path: String,
+) -> Result<usize, String> {
+    state.parameter_manager
+        .export_configs(config_ids, std::path::Path::new(&output_path))
+        .await
+}
+
+#[tauri::command]
+pub async fn import_launch_configs(
+    state: State<'_, AppState>,
+    input_path: String,
+    conflict_strategy: ConfigConflictStrategy,
+    browser_id: Option<String>,
+) -> Result<LaunchConfigImportReport, String> {
+    state.parameter_manager
+        .import_configs(std::path::Path::new(&input_path), conflict_strategy, browser_id)
+        .await
+}
+
 // 健康检查命令
 #[tauri::command]
 pub async fn health_check() -> Result<String, String> {
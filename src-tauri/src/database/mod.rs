@@ -1,4 +1,4 @@
-use crate::models::BrowserInfo;
+use crate::models::{BrowserInfo, BrowserType, LaunchRecord, ParameterTemplate, Platform, PolicySet, TrashEntry};
 use sqlx::{sqlite::SqlitePool, Row, SqlitePool as Pool};
 use std::path::Path;
 
@@ -32,6 +32,7 @@ impl Database {
                 download_date TEXT NOT NULL,
                 file_size INTEGER NOT NULL,
                 is_running BOOLEAN DEFAULT FALSE,
+                is_external BOOLEAN DEFAULT FALSE,
                 created_at TEXT DEFAULT CURRENT_TIMESTAMP
             )
             "#,
@@ -39,6 +40,128 @@ impl Database {
         .execute(pool)
         .await?;
 
+        // 创建用户自定义参数模板表
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS parameter_templates (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                description TEXT NOT NULL,
+                category TEXT NOT NULL,
+                parameters TEXT NOT NULL,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        // 创建启动历史表
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS launch_history (
+                id TEXT PRIMARY KEY,
+                browser_id TEXT NOT NULL,
+                browser_version TEXT NOT NULL,
+                executable_path TEXT NOT NULL,
+                args TEXT NOT NULL,
+                env TEXT NOT NULL,
+                working_dir TEXT,
+                config_ids TEXT NOT NULL,
+                profile_dir TEXT,
+                pid INTEGER,
+                started_at TEXT NOT NULL,
+                stopped_at TEXT,
+                exit_code INTEGER,
+                log_dir TEXT,
+                relaunch_of TEXT
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_launch_history_browser ON launch_history (browser_id, started_at)",
+        )
+        .execute(pool)
+        .await?;
+
+        // 创建浏览器回收站表
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS browser_trash (
+                id TEXT PRIMARY KEY,
+                browser_id TEXT NOT NULL,
+                browser TEXT NOT NULL,
+                trash_path TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                trashed_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        // 创建企业策略集表
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS policy_sets (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                description TEXT NOT NULL,
+                policies TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        // 旧版本数据库补充新增列
+        Self::ensure_column(pool, "browsers", "is_external", "BOOLEAN DEFAULT FALSE").await?;
+        Self::ensure_column(pool, "browsers", "last_launched", "TEXT").await?;
+        Self::ensure_column(pool, "launch_history", "firefox_prefs", "TEXT NOT NULL DEFAULT '{}'").await?;
+        Self::ensure_column(pool, "launch_history", "profile_seed", "TEXT NOT NULL DEFAULT '{}'").await?;
+        Self::ensure_column(pool, "launch_history", "policies", "TEXT NOT NULL DEFAULT '{}'").await?;
+        Self::ensure_column(pool, "parameter_templates", "browser_family", "TEXT NOT NULL DEFAULT 'chromium'").await?;
+
+        // 旧版本以枚举变体名（如 ChromeDriver）保存浏览器类型
+        for browser_type in BrowserType::ALL {
+            sqlx::query("UPDATE browsers SET browser_type = ?1 WHERE browser_type = ?2")
+                .bind(browser_type.as_str())
+                .bind(format!("{:?}", browser_type))
+                .execute(pool)
+                .await?;
+        }
+
+        // 旧版本的平台写法统一为规范名称
+        for (legacy, platform) in [
+            ("linux", Platform::Linux64),
+            ("linux_arm64", Platform::LinuxArm64),
+            ("mac_x64", Platform::MacX64),
+            ("mac_arm", Platform::MacArm64),
+        ] {
+            sqlx::query("UPDATE browsers SET platform = ?1 WHERE platform = ?2")
+                .bind(platform.as_str())
+                .bind(legacy)
+                .execute(pool)
+                .await?;
+        }
+
+        // 同一构建只保留一条受管理的记录
+        Self::remove_duplicate_browsers(pool).await?;
+        sqlx::query(
+            r#"
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_browsers_build
+            ON browsers (browser_type, version, platform) WHERE is_external = 0
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
         // 创建下载任务表
         sqlx::query(
             r#"
@@ -77,23 +200,104 @@ impl Database {
         Ok(())
     }
 
+    /// 删除指向同一构建的重复浏览器记录，保留最早下载的一条
+    async fn remove_duplicate_browsers(pool: &Pool) -> Result<(), sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT b.id, (
+                SELECT k.id FROM browsers k
+                WHERE k.is_external = 0
+                  AND k.browser_type = b.browser_type
+                  AND k.version = b.version
+                  AND k.platform = b.platform
+                ORDER BY k.download_date, k.rowid
+                LIMIT 1
+            ) AS kept_id
+            FROM browsers b
+            WHERE b.is_external = 0
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let duplicates: Vec<(String, String)> = rows
+            .iter()
+            .map(|row| (row.get("id"), row.get("kept_id")))
+            .filter(|(id, kept_id)| id != kept_id)
+            .collect();
+        if duplicates.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = pool.begin().await?;
+        for (id, kept_id) in &duplicates {
+            // 启动历史改为指向保留的记录
+            sqlx::query("UPDATE launch_history SET browser_id = ?1 WHERE browser_id = ?2")
+                .bind(kept_id)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("DELETE FROM browsers WHERE id = ?1")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        tracing::info!("Removed {} duplicate browser record(s)", duplicates.len());
+        Ok(())
+    }
+
+    /// 如果表中缺少指定列则添加
+    async fn ensure_column(
+        pool: &Pool,
+        table: &str,
+        column: &str,
+        definition: &str,
+    ) -> Result<(), sqlx::Error> {
+        let columns = sqlx::query(&format!("PRAGMA table_info({})", table))
+            .fetch_all(pool)
+            .await?;
+
+        if !columns.iter().any(|row| row.get::<String, _>("name") == column) {
+            sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+                .execute(pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+
     pub async fn save_browser(&self, browser: &BrowserInfo) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
-            INSERT OR REPLACE INTO browsers 
-            (id, browser_type, version, platform, install_path, executable_path, download_date, file_size, is_running)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            INSERT INTO browsers 
+            (id, browser_type, version, platform, install_path, executable_path, download_date, file_size, is_running, is_external, last_launched)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            ON CONFLICT(id) DO UPDATE SET
+                browser_type = excluded.browser_type,
+                version = excluded.version,
+                platform = excluded.platform,
+                install_path = excluded.install_path,
+                executable_path = excluded.executable_path,
+                download_date = excluded.download_date,
+                file_size = excluded.file_size,
+                is_running = excluded.is_running,
+                is_external = excluded.is_external,
+                last_launched = excluded.last_launched
             "#,
         )
         .bind(&browser.id)
-        .bind(format!("{:?}", browser.browser_type))
+        .bind(browser.browser_type.as_str())
         .bind(&browser.version)
-        .bind(&browser.platform)
+        .bind(browser.platform.as_str())
         .bind(browser.install_path.to_string_lossy().as_ref())
         .bind(browser.executable_path.to_string_lossy().as_ref())
         .bind(browser.download_date.to_rfc3339())
         .bind(browser.file_size as i64)
         .bind(browser.is_running)
+        .bind(browser.is_external)
+        .bind(browser.last_launched.map(|t| t.to_rfc3339()))
         .execute(&self.pool)
         .await?;
 
@@ -107,19 +311,19 @@ impl Database {
 
         let mut browsers = Vec::new();
         for row in rows {
-            let browser_type = match row.get::<String, _>("browser_type").as_str() {
-                "Chrome" => crate::models::BrowserType::Chrome,
-                "Chromium" => crate::models::BrowserType::Chromium,
-                "Firefox" => crate::models::BrowserType::Firefox,
-                "ChromeDriver" => crate::models::BrowserType::ChromeDriver,
-                _ => crate::models::BrowserType::Chrome,
-            };
+            let browser_type: BrowserType = row
+                .get::<String, _>("browser_type")
+                .parse()
+                .map_err(|e: String| sqlx::Error::Decode(e.into()))?;
 
             let browser = BrowserInfo {
                 id: row.get("id"),
                 browser_type,
                 version: row.get("version"),
-                platform: row.get("platform"),
+                platform: row
+                    .get::<String, _>("platform")
+                    .parse()
+                    .map_err(|e: String| sqlx::Error::Decode(e.into()))?,
                 install_path: row.get::<String, _>("install_path").into(),
                 executable_path: row.get::<String, _>("executable_path").into(),
                 download_date: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("download_date"))
@@ -127,6 +331,11 @@ impl Database {
                     .with_timezone(&chrono::Utc),
                 file_size: row.get::<i64, _>("file_size") as u64,
                 is_running: row.get("is_running"),
+                is_external: row.get("is_external"),
+                last_launched: row
+                    .get::<Option<String>, _>("last_launched")
+                    .and_then(|t| chrono::DateTime::parse_from_rfc3339(&t).ok())
+                    .map(|t| t.with_timezone(&chrono::Utc)),
             };
 
             browsers.push(browser);
@@ -135,6 +344,32 @@ impl Database {
         Ok(browsers)
     }
 
+    /// 记录浏览器最近一次启动时间
+    pub async fn set_browser_last_launched(
+        &self,
+        id: &str,
+        launched_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE browsers SET last_launched = ?1 WHERE id = ?2")
+            .bind(launched_at.to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 更新浏览器占用的磁盘空间
+    pub async fn set_browser_file_size(&self, id: &str, file_size: u64) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE browsers SET file_size = ?1 WHERE id = ?2")
+            .bind(file_size as i64)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn delete_browser(&self, id: &str) -> Result<(), sqlx::Error> {
         sqlx::query("DELETE FROM browsers WHERE id = ?1")
             .bind(id)
@@ -144,6 +379,364 @@ impl Database {
         Ok(())
     }
 
+    pub async fn save_template(&self, template: &ParameterTemplate) -> Result<(), sqlx::Error> {
+        let category = serde_json::to_value(&template.category)
+            .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+        let parameters = serde_json::to_string(&template.parameters)
+            .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO parameter_templates (id, name, description, category, parameters, browser_family)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                description = excluded.description,
+                category = excluded.category,
+                parameters = excluded.parameters,
+                browser_family = excluded.browser_family,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(&template.id)
+        .bind(&template.name)
+        .bind(&template.description)
+        .bind(category.as_str().unwrap_or_default())
+        .bind(parameters)
+        .bind(template.browser_family.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_templates(&self) -> Result<Vec<ParameterTemplate>, sqlx::Error> {
+        let rows = sqlx::query("SELECT * FROM parameter_templates ORDER BY created_at ASC")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut templates = Vec::new();
+        for row in rows {
+            let category = serde_json::from_value(serde_json::Value::String(row.get("category")))
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+            let parameters = serde_json::from_str(&row.get::<String, _>("parameters"))
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+            let browser_family = row
+                .get::<String, _>("browser_family")
+                .parse()
+                .map_err(|e: String| sqlx::Error::Decode(e.into()))?;
+
+            templates.push(ParameterTemplate {
+                id: row.get("id"),
+                name: row.get("name"),
+                description: row.get("description"),
+                category,
+                browser_family,
+                parameters,
+                is_builtin: false,
+            });
+        }
+
+        Ok(templates)
+    }
+
+    pub async fn delete_template(&self, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM parameter_templates WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn save_policy_set(&self, policy_set: &PolicySet) -> Result<(), sqlx::Error> {
+        let policies = serde_json::to_string(&policy_set.policies)
+            .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO policy_sets (id, name, description, policies, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                description = excluded.description,
+                policies = excluded.policies,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&policy_set.id)
+        .bind(&policy_set.name)
+        .bind(&policy_set.description)
+        .bind(policies)
+        .bind(policy_set.created_at.to_rfc3339())
+        .bind(policy_set.updated_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_policy_sets(&self) -> Result<Vec<PolicySet>, sqlx::Error> {
+        let rows = sqlx::query("SELECT * FROM policy_sets ORDER BY created_at ASC")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let parse_time = |value: String| {
+            chrono::DateTime::parse_from_rfc3339(&value)
+                .map(|t| t.with_timezone(&chrono::Utc))
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))
+        };
+
+        let mut policy_sets = Vec::new();
+        for row in rows {
+            let policies = serde_json::from_str(&row.get::<String, _>("policies"))
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+            policy_sets.push(PolicySet {
+                id: row.get("id"),
+                name: row.get("name"),
+                description: row.get("description"),
+                policies,
+                created_at: parse_time(row.get("created_at"))?,
+                updated_at: parse_time(row.get("updated_at"))?,
+            });
+        }
+
+        Ok(policy_sets)
+    }
+
+    pub async fn delete_policy_set(&self, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM policy_sets WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn save_launch_record(&self, record: &LaunchRecord) -> Result<(), sqlx::Error> {
+        let to_json = |value: serde_json::Result<String>| {
+            value.map_err(|e| sqlx::Error::Encode(Box::new(e)))
+        };
+
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO launch_history
+            (id, browser_id, browser_version, executable_path, args, env, working_dir, config_ids,
+             profile_dir, pid, started_at, stopped_at, exit_code, log_dir, relaunch_of, firefox_prefs,
+             profile_seed, policies)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
+            "#,
+        )
+        .bind(&record.id)
+        .bind(&record.browser_id)
+        .bind(&record.browser_version)
+        .bind(&record.executable_path)
+        .bind(to_json(serde_json::to_string(&record.args))?)
+        .bind(to_json(serde_json::to_string(&record.env))?)
+        .bind(&record.working_dir)
+        .bind(to_json(serde_json::to_string(&record.config_ids))?)
+        .bind(&record.profile_dir)
+        .bind(record.pid.map(|pid| pid as i64))
+        .bind(record.started_at.to_rfc3339())
+        .bind(record.stopped_at.map(|t| t.to_rfc3339()))
+        .bind(record.exit_code)
+        .bind(&record.log_dir)
+        .bind(&record.relaunch_of)
+        .bind(to_json(serde_json::to_string(&record.firefox_prefs))?)
+        .bind(to_json(serde_json::to_string(&record.profile_seed))?)
+        .bind(to_json(serde_json::to_string(&record.policies))?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 记录浏览器进程退出
+    pub async fn finish_launch_record(
+        &self,
+        id: &str,
+        exit_code: Option<i32>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE launch_history SET stopped_at = ?1, exit_code = ?2 WHERE id = ?3")
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(exit_code)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 在同一事务中删除浏览器记录并写入回收站记录
+    pub async fn move_browsers_to_trash(&self, entries: &[TrashEntry]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for entry in entries {
+            let browser = serde_json::to_string(&entry.browser)
+                .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+
+            sqlx::query("DELETE FROM browsers WHERE id = ?1")
+                .bind(&entry.browser.id)
+                .execute(&mut *tx)
+                .await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO browser_trash (id, browser_id, browser, trash_path, size_bytes, trashed_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+            )
+            .bind(&entry.id)
+            .bind(&entry.browser.id)
+            .bind(browser)
+            .bind(&entry.trash_path)
+            .bind(entry.size_bytes as i64)
+            .bind(entry.trashed_at.to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    /// 在同一事务中恢复浏览器记录并删除回收站记录
+    pub async fn restore_browser_from_trash(&self, entry: &TrashEntry) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let browser = &entry.browser;
+
+        sqlx::query(
+            r#"
+            INSERT INTO browsers
+            (id, browser_type, version, platform, install_path, executable_path, download_date, file_size, is_running, is_external, last_launched)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            "#,
+        )
+        .bind(&browser.id)
+        .bind(browser.browser_type.as_str())
+        .bind(&browser.version)
+        .bind(browser.platform.as_str())
+        .bind(browser.install_path.to_string_lossy().as_ref())
+        .bind(browser.executable_path.to_string_lossy().as_ref())
+        .bind(browser.download_date.to_rfc3339())
+        .bind(browser.file_size as i64)
+        .bind(false)
+        .bind(browser.is_external)
+        .bind(browser.last_launched.map(|t| t.to_rfc3339()))
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM browser_trash WHERE id = ?1")
+            .bind(&entry.id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await
+    }
+
+    pub async fn get_trash_entries(&self) -> Result<Vec<TrashEntry>, sqlx::Error> {
+        let rows = sqlx::query("SELECT * FROM browser_trash ORDER BY trashed_at DESC")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                let browser = serde_json::from_str(&row.get::<String, _>("browser"))
+                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+                let trashed_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("trashed_at"))
+                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?
+                    .with_timezone(&chrono::Utc);
+
+                Ok(TrashEntry {
+                    id: row.get("id"),
+                    browser,
+                    trash_path: row.get("trash_path"),
+                    size_bytes: row.get::<i64, _>("size_bytes") as u64,
+                    trashed_at,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn delete_trash_entry(&self, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM browser_trash WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 按启动时间倒序获取启动历史
+    pub async fn get_launch_history(
+        &self,
+        browser_id: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<LaunchRecord>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM launch_history
+            WHERE ?1 IS NULL OR browser_id = ?1
+            ORDER BY started_at DESC
+            LIMIT ?2
+            "#,
+        )
+        .bind(browser_id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::launch_record_from_row).collect()
+    }
+
+    pub async fn get_launch_record(&self, id: &str) -> Result<Option<LaunchRecord>, sqlx::Error> {
+        let row = sqlx::query("SELECT * FROM launch_history WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(Self::launch_record_from_row).transpose()
+    }
+
+    fn launch_record_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<LaunchRecord, sqlx::Error> {
+        fn from_json<T: serde::de::DeserializeOwned>(
+            row: &sqlx::sqlite::SqliteRow,
+            column: &str,
+        ) -> Result<T, sqlx::Error> {
+            serde_json::from_str(&row.get::<String, _>(column))
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))
+        }
+        let parse_time = |value: String| {
+            chrono::DateTime::parse_from_rfc3339(&value)
+                .map(|t| t.with_timezone(&chrono::Utc))
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))
+        };
+
+        Ok(LaunchRecord {
+            id: row.get("id"),
+            browser_id: row.get("browser_id"),
+            browser_version: row.get("browser_version"),
+            executable_path: row.get("executable_path"),
+            args: from_json(row, "args")?,
+            env: from_json(row, "env")?,
+            working_dir: row.get("working_dir"),
+            config_ids: from_json(row, "config_ids")?,
+            profile_dir: row.get("profile_dir"),
+            pid: row.get::<Option<i64>, _>("pid").map(|pid| pid as u32),
+            started_at: parse_time(row.get("started_at"))?,
+            stopped_at: row
+                .get::<Option<String>, _>("stopped_at")
+                .map(parse_time)
+                .transpose()?,
+            exit_code: row.get("exit_code"),
+            log_dir: row.get("log_dir"),
+            relaunch_of: row.get("relaunch_of"),
+            firefox_prefs: from_json(row, "firefox_prefs")?,
+            profile_seed: from_json(row, "profile_seed")?,
+            policies: from_json(row, "policies")?,
+        })
+    }
+
     pub async fn update_download_progress(
         &self,
         task_id: &str,
@@ -44,10 +44,33 @@ pub fn run() {
             // 浏览器管理命令
             commands::list_browsers,
             commands::download_browser,
+            commands::import_browser_archive,
+            commands::discover_system_browsers,
+            commands::register_system_browser,
+            commands::unregister_browser,
             commands::delete_browser,
             commands::clear_all_browsers,
             commands::open_browser,
+            commands::tail_launch_log,
+            commands::list_launch_history,
+            commands::relaunch,
+            commands::launch_matrix,
+            commands::stop_launches,
+            commands::capture_page,
+            commands::compare_screenshots,
+            commands::save_visual_baseline,
+            commands::get_storage_usage,
+            commands::get_retention_policy,
+            commands::set_retention_policy,
+            commands::apply_retention_policy,
+            commands::scan_and_repair,
+            commands::list_browser_trash,
+            commands::restore_browser,
+            commands::purge_browser_trash,
             commands::get_browser_info,
+            // 浏览器安装包命令
+            commands::export_browser_bundle,
+            commands::import_browser_bundle,
             // 下载管理命令
             commands::get_download_progress,
             commands::retry_download,
@@ -71,9 +94,30 @@ pub fn run() {
             commands::set_default_launch_config,
             commands::get_launch_templates,
             commands::get_launch_templates_by_category,
+            commands::create_template_from_config,
+            commands::update_launch_template,
+            commands::delete_launch_template,
             commands::build_browser_launch_args,
+            commands::resolve_browser_launch_args,
+            commands::explain_launch_args,
+            commands::get_builtin_launch_variables,
+            commands::get_launch_layer_states,
+            commands::set_launch_layer_enabled,
             commands::validate_config_security,
+            commands::check_launch_security,
+            commands::get_security_policy,
+            commands::reload_security_policy,
+            commands::validate_launch_config_flags,
+            commands::get_chrome_switch_catalog,
+            commands::get_chrome_policy_catalog,
+            commands::get_policy_sets,
+            commands::create_policy_set,
+            commands::update_policy_set,
+            commands::delete_policy_set,
+            commands::validate_policies,
             commands::update_config_parameters,
+            commands::export_launch_configs,
+            commands::import_launch_configs,
             // 健康检查命令
             commands::health_check,
         ])
@@ -0,0 +1,50 @@
+use super::{BrowserInfo, BrowserLaunchConfig, Platform};
+use serde::{Deserialize, Serialize};
+
+/// 当前浏览器安装包格式版本
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// 安装包清单（manifest.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub platform: Platform,
+    pub browsers: Vec<BundledBrowser>,
+    pub launch_configs: Vec<BrowserLaunchConfig>,
+}
+
+/// 安装包中的单个浏览器
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledBrowser {
+    pub info: BrowserInfo,
+    pub executable_relative_path: String, // 可执行文件相对安装目录的路径
+    pub files: Vec<BundleFileEntry>,
+}
+
+/// 安装包中文件的校验信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleFileEntry {
+    pub path: String, // 相对安装目录的路径，使用 '/' 分隔
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// 安装包导入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleImportReport {
+    pub imported_browsers: Vec<BrowserInfo>,
+    pub imported_configs: Vec<BrowserLaunchConfig>,
+    pub skipped: Vec<String>, // 跳过的项目及原因
+}
+
+impl BundledBrowser {
+    /// 安装包内存放该浏览器文件的目录
+    pub fn archive_dir(&self) -> String {
+        format!("browsers/{}", self.info.id)
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+}
@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+
+/// 页面捕获的输出格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum CaptureFormat {
+    Screenshot,
+    Pdf,
+}
+
+impl CaptureFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CaptureFormat::Screenshot => "png",
+            CaptureFormat::Pdf => "pdf",
+        }
+    }
+
+    /// 无头模式下输出到指定文件的参数
+    pub fn output_args(&self, path: &str) -> Vec<String> {
+        match self {
+            CaptureFormat::Screenshot => vec![format!("--screenshot={}", path)],
+            CaptureFormat::Pdf => vec![
+                format!("--print-to-pdf={}", path),
+                "--no-pdf-header-footer".to_string(),
+            ],
+        }
+    }
+}
+
+/// 页面捕获请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureRequest {
+    pub url: String, // http(s)、file:// 或本地文件路径
+    pub browser_ids: Vec<String>,
+    #[serde(default = "default_capture_formats")]
+    pub formats: Vec<CaptureFormat>,
+    #[serde(default = "default_viewport_width")]
+    pub viewport_width: u32,
+    #[serde(default = "default_viewport_height")]
+    pub viewport_height: u32,
+    #[serde(default)]
+    pub config_id: Option<String>, // 额外应用的启动配置
+    #[serde(default)]
+    pub wait_ms: Option<u32>, // 页面加载后等待的虚拟时间
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub no_sandbox: bool, // 以 root 身份运行或在容器中时需要
+    #[serde(default)]
+    pub acknowledged: bool, // 已确认安全风险
+}
+
+fn default_capture_formats() -> Vec<CaptureFormat> {
+    vec![CaptureFormat::Screenshot]
+}
+
+fn default_viewport_width() -> u32 {
+    1280
+}
+
+fn default_viewport_height() -> u32 {
+    800
+}
+
+/// 单个浏览器版本的捕获结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureArtifact {
+    pub browser_id: String,
+    pub browser_version: String,
+    pub format: CaptureFormat,
+    pub path: Option<String>, // 捕获失败时为空
+    pub file_size: u64,
+    pub args: Vec<String>,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+/// 一次页面捕获（元数据同时写入 capture.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureResult {
+    pub capture_id: String,
+    pub url: String,
+    pub captured_at: chrono::DateTime<chrono::Utc>,
+    pub artifacts_dir: String,
+    pub viewport_width: u32,
+    pub viewport_height: u32,
+    pub artifacts: Vec<CaptureArtifact>,
+}
+
+/// 视觉对比的图像来源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum VisualDiffSource {
+    Capture { capture_id: String, browser_id: String }, // 某次捕获中指定浏览器的截图
+    Baseline { name: String },                         // 保存的基准截图
+    File { path: String },
+}
+
+/// 视觉对比选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisualDiffOptions {
+    #[serde(default = "default_pixel_tolerance")]
+    pub pixel_tolerance: f64, // 单个像素的颜色容差（0~1）
+    #[serde(default = "default_max_diff_ratio")]
+    pub max_diff_ratio: f64, // 允许的不同像素比例
+    #[serde(default)]
+    pub max_perceptual_difference: Option<f64>, // 允许的感知差异（0~1）
+}
+
+impl Default for VisualDiffOptions {
+    fn default() -> Self {
+        Self {
+            pixel_tolerance: default_pixel_tolerance(),
+            max_diff_ratio: default_max_diff_ratio(),
+            max_perceptual_difference: None,
+        }
+    }
+}
+
+fn default_pixel_tolerance() -> f64 {
+    0.1
+}
+
+fn default_max_diff_ratio() -> f64 {
+    0.001
+}
+
+/// 视觉对比结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisualDiffResult {
+    pub diff_id: String,
+    pub baseline_path: String,
+    pub candidate_path: String,
+    pub width: u32,
+    pub height: u32,
+    pub size_mismatch: bool,
+    pub different_pixels: u64,
+    pub total_pixels: u64,
+    pub diff_ratio: f64,
+    pub perceptual_difference: f64,
+    pub diff_image_path: String, // 标出差异的图像
+    pub options: VisualDiffOptions,
+    pub passed: bool,
+    pub compared_at: chrono::DateTime<chrono::Utc>,
+}
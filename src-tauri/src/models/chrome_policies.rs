@@ -0,0 +1,109 @@
+use super::BrowserType;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// 内置的 Chrome 企业策略目录
+const CATALOG_JSON: &str = include_str!("chrome_policies.json");
+
+/// 策略目录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyCatalog {
+    pub policies: Vec<PolicyDefinition>,
+}
+
+/// 单个企业策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyDefinition {
+    pub name: String,
+    pub value_type: PolicyValueType,
+    #[serde(default)]
+    pub item_type: Option<PolicyValueType>, // 列表项的类型，未指定时为字符串
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_values: Vec<serde_json::Value>,
+    pub description: String,
+    #[serde(default)]
+    pub deprecated: bool,
+}
+
+/// 策略取值类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum PolicyValueType {
+    Boolean,
+    Integer,
+    String,
+    List,
+    Dictionary,
+}
+
+impl PolicyCatalog {
+    /// 获取内置目录（首次访问时解析）
+    pub fn builtin() -> &'static PolicyCatalog {
+        static CATALOG: OnceLock<PolicyCatalog> = OnceLock::new();
+        CATALOG.get_or_init(|| {
+            serde_json::from_str(CATALOG_JSON).expect("bundled chrome_policies.json is invalid")
+        })
+    }
+
+    /// 按名称查找策略（策略名区分大小写）
+    pub fn find(&self, name: &str) -> Option<&PolicyDefinition> {
+        self.policies.iter().find(|p| p.name == name)
+    }
+}
+
+impl PolicyValueType {
+    pub fn matches(&self, value: &serde_json::Value) -> bool {
+        match self {
+            PolicyValueType::Boolean => value.is_boolean(),
+            PolicyValueType::Integer => value.is_i64(),
+            PolicyValueType::String => value.is_string(),
+            PolicyValueType::List => value.is_array(),
+            PolicyValueType::Dictionary => value.is_object(),
+        }
+    }
+}
+
+/// 企业策略集，可被多个启动配置引用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicySet {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub policies: BTreeMap<String, serde_json::Value>, // 策略名 -> 取值，即写入策略文件的 JSON
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl PolicySet {
+    pub fn new(name: String, description: String) -> Self {
+        let now = chrono::Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            description,
+            policies: BTreeMap::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// 企业策略在启动时的应用方式
+///
+/// 正式版 Chrome（包括 Chrome for Testing）只读取系统级的托管策略目录，
+/// 该目录需要管理员权限且对机器上的所有实例生效，因此不支持按次启动应用策略。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PolicyDelivery {
+    CommandLine, // 通过 --policy 开关传入，只有 Chromium 等非正式版构建会读取
+}
+
+impl PolicyDelivery {
+    /// 浏览器构建支持的策略应用方式，不支持时返回 None
+    pub fn for_browser(browser_type: &BrowserType) -> Option<PolicyDelivery> {
+        match browser_type {
+            BrowserType::Chromium => Some(PolicyDelivery::CommandLine),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// 内置的 Chrome 命令行开关目录
+const CATALOG_JSON: &str = include_str!("chrome_switches.json");
+
+/// 开关目录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwitchCatalog {
+    pub switches: Vec<ChromeSwitch>,
+    pub v8_flags: Vec<String>, // 只能通过 --js-flags 传递的 V8 参数
+}
+
+/// 单个 Chrome 命令行开关
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChromeSwitch {
+    pub name: String, // 不带前缀 "--"
+    pub value_type: SwitchValueType,
+    #[serde(default)]
+    pub value_required: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_values: Vec<String>,
+    pub description: String,
+    #[serde(default)]
+    pub min_version: Option<u32>, // 最早支持的主版本
+    #[serde(default)]
+    pub deprecated_since: Option<u32>,
+    #[serde(default)]
+    pub removed_in: Option<u32>,
+    #[serde(default)]
+    pub replacement: Option<String>,
+    #[serde(default)]
+    pub is_dangerous: bool,
+}
+
+/// 开关取值类型
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum SwitchValueType {
+    None,    // 不接受值
+    Integer,
+    Float,
+    String,
+    Path,
+    Url,
+    List,    // 逗号分隔的列表
+    Size,    // "宽,高" 或 "x,y"
+    Enum,    // allowed_values 中的值
+}
+
+impl SwitchCatalog {
+    /// 获取内置目录（首次访问时解析）
+    pub fn builtin() -> &'static SwitchCatalog {
+        static CATALOG: OnceLock<SwitchCatalog> = OnceLock::new();
+        CATALOG.get_or_init(|| {
+            serde_json::from_str(CATALOG_JSON).expect("bundled chrome_switches.json is invalid")
+        })
+    }
+
+    /// 按名称查找开关（名称可带或不带 "--" 前缀）
+    pub fn find(&self, name: &str) -> Option<&ChromeSwitch> {
+        let name = name.trim_start_matches('-');
+        self.switches.iter().find(|s| s.name == name)
+    }
+
+    pub fn is_v8_flag(&self, name: &str) -> bool {
+        let name = name.trim_start_matches('-').replace('_', "-");
+        self.v8_flags.contains(&name)
+    }
+}
+
+impl ChromeSwitch {
+    pub fn flag(&self) -> String {
+        format!("--{}", self.name)
+    }
+}
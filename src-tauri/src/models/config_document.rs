@@ -0,0 +1,217 @@
+use super::{
+    BrowserLaunchConfig, BrowserType, LaunchParameter, ParameterCategory, ProfileSeed, VersionRequirement,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// 当前启动配置文档格式版本
+pub const LAUNCH_CONFIG_DOCUMENT_VERSION: u32 = 1;
+
+/// 可共享的启动配置文档（不包含ID、时间戳等本机相关信息）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchConfigDocument {
+    pub version: u32,
+    #[serde(default)]
+    pub exported_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub configs: Vec<PortableLaunchConfig>,
+}
+
+/// 可移植的启动配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortableLaunchConfig {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_true")]
+    pub is_enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser_type: Option<BrowserType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_range: Option<VersionRequirement>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>, // 父配置的名称（本机ID在其他机器上没有意义）
+    pub parameters: Vec<PortableLaunchParameter>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub firefox_prefs: BTreeMap<String, serde_json::Value>,
+    #[serde(default, skip_serializing_if = "ProfileSeed::is_empty")]
+    pub profile_seed: ProfileSeed,
+}
+
+/// 可移植的启动参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortableLaunchParameter {
+    pub name: String,
+    pub flag: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_category")]
+    pub category: ParameterCategory,
+    #[serde(default = "default_true")]
+    pub is_enabled: bool,
+    #[serde(default)]
+    pub is_dangerous: bool,
+}
+
+/// 文档序列化格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ConfigDocumentFormat {
+    Json,
+    Yaml,
+}
+
+/// 导入时同名配置的处理方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ConfigConflictStrategy {
+    Skip,      // 跳过同名配置
+    Overwrite, // 覆盖同名配置的内容（保留原ID）
+    Rename,    // 以新名称导入
+}
+
+/// 启动配置导入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchConfigImportReport {
+    pub imported: Vec<BrowserLaunchConfig>,
+    pub skipped: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_category() -> ParameterCategory {
+    ParameterCategory::Custom
+}
+
+impl ConfigDocumentFormat {
+    /// 根据文件扩展名判断格式
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "json" => Some(ConfigDocumentFormat::Json),
+            "yaml" | "yml" => Some(ConfigDocumentFormat::Yaml),
+            _ => None,
+        }
+    }
+}
+
+impl LaunchConfigDocument {
+    /// all_configs 用于将继承的父配置ID转换为名称
+    pub fn new(
+        configs: &[BrowserLaunchConfig],
+        all_configs: &HashMap<String, BrowserLaunchConfig>,
+    ) -> Self {
+        Self {
+            version: LAUNCH_CONFIG_DOCUMENT_VERSION,
+            exported_at: Some(chrono::Utc::now()),
+            configs: configs
+                .iter()
+                .map(|config| {
+                    let mut portable = PortableLaunchConfig::from(config);
+                    portable.extends = config
+                        .extends
+                        .as_ref()
+                        .and_then(|id| all_configs.get(id))
+                        .map(|parent| parent.name.clone());
+                    portable
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_text(&self, format: ConfigDocumentFormat) -> Result<String, String> {
+        match format {
+            ConfigDocumentFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(|e| format!("Failed to serialize launch configs: {}", e)),
+            ConfigDocumentFormat::Yaml => serde_yaml::to_string(self)
+                .map_err(|e| format!("Failed to serialize launch configs: {}", e)),
+        }
+    }
+
+    pub fn parse(content: &str, format: ConfigDocumentFormat) -> Result<Self, String> {
+        let document: Self = match format {
+            ConfigDocumentFormat::Json => serde_json::from_str(content)
+                .map_err(|e| format!("Invalid launch config document: {}", e))?,
+            ConfigDocumentFormat::Yaml => serde_yaml::from_str(content)
+                .map_err(|e| format!("Invalid launch config document: {}", e))?,
+        };
+
+        if document.version > LAUNCH_CONFIG_DOCUMENT_VERSION {
+            return Err(format!(
+                "Unsupported launch config document version: {} (supported up to {})",
+                document.version, LAUNCH_CONFIG_DOCUMENT_VERSION
+            ));
+        }
+
+        Ok(document)
+    }
+}
+
+impl From<&BrowserLaunchConfig> for PortableLaunchConfig {
+    fn from(config: &BrowserLaunchConfig) -> Self {
+        Self {
+            name: config.name.clone(),
+            description: config.description.clone(),
+            is_enabled: config.is_enabled,
+            browser_type: config.browser_type.clone(),
+            version_range: config.version_range.clone(),
+            extends: None,
+            env: config.env.clone(),
+            working_dir: config.working_dir.clone(),
+            variables: config.variables.clone(),
+            firefox_prefs: config.firefox_prefs.clone(),
+            profile_seed: config.profile_seed.clone(),
+            parameters: config
+                .parameters
+                .iter()
+                .map(|p| PortableLaunchParameter {
+                    name: p.name.clone(),
+                    flag: p.flag.clone(),
+                    value: p.value.clone(),
+                    description: p.description.clone(),
+                    category: p.category.clone(),
+                    is_enabled: p.is_enabled,
+                    is_dangerous: p.is_dangerous,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl PortableLaunchConfig {
+    /// 转换为新的启动配置（生成新的ID，继承关系需要调用方按名称解析）
+    pub fn into_config(self, browser_id: Option<String>) -> BrowserLaunchConfig {
+        let mut config = BrowserLaunchConfig::new(self.name, self.description);
+        config.browser_id = browser_id;
+        config.is_enabled = self.is_enabled;
+        config.browser_type = self.browser_type;
+        config.version_range = self.version_range;
+        config.env = self.env;
+        config.working_dir = self.working_dir;
+        config.variables = self.variables;
+        config.firefox_prefs = self.firefox_prefs;
+        config.profile_seed = self.profile_seed;
+        config.parameters = self
+            .parameters
+            .into_iter()
+            .map(|p| {
+                let mut parameter = LaunchParameter::new(p.name, p.flag, p.category)
+                    .with_description(p.description);
+                parameter.value = p.value;
+                parameter.is_enabled = p.is_enabled;
+                parameter.is_dangerous = p.is_dangerous;
+                parameter
+            })
+            .collect();
+        config
+    }
+}
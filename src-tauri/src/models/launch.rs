@@ -0,0 +1,172 @@
+use super::ProfileSeed;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 启动浏览器进程的选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LaunchOptions {
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    #[serde(default)]
+    pub acknowledged: bool, // 已确认安全风险
+    #[serde(default)]
+    pub config_ids: Vec<String>, // 参与合并的启动配置
+    #[serde(default)]
+    pub relaunch_of: Option<String>, // 重新启动时对应的原启动记录ID
+    #[serde(default)]
+    pub firefox_prefs: BTreeMap<String, serde_json::Value>, // 写入 Firefox 配置文件 user.js 的首选项
+    #[serde(default)]
+    pub profile_seed: ProfileSeed, // 写入 Chrome 用户数据目录的初始设置
+    #[serde(default)]
+    pub policies: BTreeMap<String, serde_json::Value>, // 本次启动应用的企业策略
+}
+
+/// 一次浏览器启动
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserLaunch {
+    pub launch_id: String,
+    pub browser_id: String,
+    pub pid: Option<u32>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub log_dir: String,
+}
+
+/// 启动历史记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchRecord {
+    pub id: String, // 即 launch_id
+    pub browser_id: String,
+    pub browser_version: String,
+    pub executable_path: String,
+    pub args: Vec<String>, // 最终的命令行参数
+    pub env: BTreeMap<String, String>,
+    pub working_dir: Option<String>,
+    pub config_ids: Vec<String>,
+    pub profile_dir: Option<String>, // --user-data-dir（Firefox 为 --profile）指定的目录
+    pub pid: Option<u32>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub stopped_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub exit_code: Option<i32>,
+    pub log_dir: Option<String>,
+    pub relaunch_of: Option<String>,
+    #[serde(default)]
+    pub firefox_prefs: BTreeMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub profile_seed: ProfileSeed,
+    #[serde(default)]
+    pub policies: BTreeMap<String, serde_json::Value>,
+}
+
+/// 多版本并排启动请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchMatrixRequest {
+    pub browser_ids: Vec<String>,
+    #[serde(default)]
+    pub config_id: Option<String>, // 所有实例共用的启动配置
+    #[serde(default)]
+    pub urls: Vec<String>, // 启动时打开的页面
+    #[serde(default)]
+    pub tiling: Option<WindowTiling>,
+    #[serde(default)]
+    pub acknowledged: bool, // 已确认安全风险
+}
+
+/// 窗口平铺布局（按行排列，未指定列数时尽量排成方形）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowTiling {
+    pub screen_width: u32,
+    pub screen_height: u32,
+    #[serde(default)]
+    pub columns: Option<u32>,
+    #[serde(default)]
+    pub origin_x: i32,
+    #[serde(default)]
+    pub origin_y: i32,
+}
+
+impl WindowTiling {
+    /// 计算第 index 个窗口（共 count 个）的位置和大小
+    pub fn bounds(&self, index: usize, count: usize) -> WindowBounds {
+        let count = count.max(1) as u32;
+        let columns = self
+            .columns
+            .unwrap_or_else(|| (count as f64).sqrt().ceil() as u32)
+            .clamp(1, count);
+        let rows = count.div_ceil(columns);
+        let width = self.screen_width / columns;
+        let height = self.screen_height / rows;
+        let index = index as u32;
+
+        WindowBounds {
+            x: self.origin_x + ((index % columns) * width) as i32,
+            y: self.origin_y + ((index / columns) * height) as i32,
+            width,
+            height,
+        }
+    }
+}
+
+/// 窗口位置和大小
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct WindowBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl WindowBounds {
+    pub fn to_args(&self) -> Vec<String> {
+        vec![
+            format!("--window-position={},{}", self.x, self.y),
+            format!("--window-size={},{}", self.width, self.height),
+        ]
+    }
+}
+
+/// 并排启动中的单个浏览器实例
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchMatrixInstance {
+    pub browser_id: String,
+    pub launch: Option<BrowserLaunch>, // 启动失败时为空
+    pub debugging_port: Option<u16>,
+    pub profile_dir: Option<String>,
+    pub window: Option<WindowBounds>,
+    pub error: Option<String>,
+}
+
+/// 一次多版本并排启动
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchMatrix {
+    pub matrix_id: String,
+    pub instances: Vec<LaunchMatrixInstance>,
+}
+
+impl LaunchMatrix {
+    /// 成功启动的实例，用于统一停止
+    pub fn launch_ids(&self) -> Vec<String> {
+        self.instances
+            .iter()
+            .filter_map(|i| i.launch.as_ref().map(|l| l.launch_id.clone()))
+            .collect()
+    }
+}
+
+/// 浏览器进程的输出流
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum LaunchLogStream {
+    Stdout,
+    Stderr,
+}
+
+impl LaunchLogStream {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            LaunchLogStream::Stdout => "stdout.log",
+            LaunchLogStream::Stderr => "stderr.log",
+        }
+    }
+}
@@ -0,0 +1,614 @@
+use super::{ConfigLayer, ProfileSeed};
+use crate::utils::{find_placeholders, substitute_placeholders};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 将 Chromium 同样接受的单横线开关（Windows 上还有 "/name"）统一为 "--name" 形式
+///
+/// 不统一时这类开关会被当作位置参数，绕过合并和安全检查。
+pub fn normalize_switch(arg: &str) -> String {
+    let rest = if arg.starts_with("--") {
+        None
+    } else if cfg!(windows) {
+        arg.strip_prefix('-').or_else(|| arg.strip_prefix('/'))
+    } else {
+        arg.strip_prefix('-')
+    };
+    match rest {
+        Some(name) if name.starts_with(|c: char| c.is_ascii_alphabetic()) => format!("--{}", name),
+        _ => arg.to_string(),
+    }
+}
+
+/// 以逗号分隔、需要逐项合并的开关，及其对立开关
+const FEATURE_LIST_SWITCHES: &[(&str, &str)] = &[
+    ("--enable-features", "--disable-features"),
+    ("--disable-features", "--enable-features"),
+    ("--enable-blink-features", "--disable-blink-features"),
+    ("--disable-blink-features", "--enable-blink-features"),
+];
+
+/// 以空格分隔的 V8 参数列表
+const JS_FLAGS_SWITCH: &str = "--js-flags";
+
+/// 内置的占位符变量（名称, 说明）
+pub const BUILTIN_LAUNCH_VARIABLES: &[(&str, &str)] = &[
+    ("browser_id", "浏览器ID"),
+    ("browser_type", "浏览器类型"),
+    ("version", "浏览器版本"),
+    ("major_version", "浏览器主版本号"),
+    ("platform", "浏览器平台"),
+    ("install_path", "安装目录"),
+    ("executable_path", "可执行文件路径"),
+    ("profile_dir", "该浏览器的用户数据目录"),
+    ("logs_dir", "启动日志目录"),
+    ("temp_dir", "系统临时目录"),
+    ("free_port", "空闲的本地端口（同一次启动中取值相同）"),
+    ("timestamp", "启动时间（YYYYMMDD-HHMMSS）"),
+];
+
+/// 最终命令行中的单个参数及其来源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedArgument {
+    pub argument: String,
+    pub switch: Option<String>, // 位置参数（如URL）为 None
+    pub sources: Vec<String>,   // 贡献了该参数（或其列表项）的来源
+}
+
+/// 被覆盖的参数记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArgumentOverride {
+    pub switch: String,
+    pub item: Option<String>, // 列表型开关中被覆盖的项（如功能名）
+    pub previous_value: Option<String>,
+    pub previous_source: String,
+    pub new_value: Option<String>,
+    pub new_source: String,
+}
+
+/// 参数合并结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchArgs {
+    pub args: Vec<String>,
+    pub arguments: Vec<ResolvedArgument>,
+    pub overrides: Vec<ArgumentOverride>, // 环境变量记为 "env:名称"，工作目录记为 "cwd"，Firefox 首选项记为 "pref:名称"，Chrome 首选项记为 "preference:路径"，企业策略记为 "policy:名称"
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>, // 替换时使用的变量（内置变量和自定义变量）
+    #[serde(default)]
+    pub unresolved_placeholders: Vec<String>, // 未定义的占位符
+    #[serde(default)]
+    pub config_ids: Vec<String>, // 实际应用的配置（按应用顺序）
+    #[serde(default)]
+    pub firefox_prefs: BTreeMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub profile_seed: ProfileSeed,
+    #[serde(default)]
+    pub policies: BTreeMap<String, serde_json::Value>,
+}
+
+/// 内置占位符变量说明
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchVariableInfo {
+    pub name: String,
+    pub description: String,
+}
+
+/// 参数层的启用状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerState {
+    pub layer: ConfigLayer,
+    pub enabled: bool,
+}
+
+/// 参与合并的配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigSource {
+    pub config_id: String,
+    pub config_name: String,
+    pub inherited_by: Option<String>, // 通过 extends 被引入时，引入它的子配置ID
+}
+
+/// 单个参数层的合并情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerExplanation {
+    pub layer: ConfigLayer,
+    pub enabled: bool,
+    pub configs: Vec<ConfigSource>, // 按应用顺序排列
+}
+
+/// 启动参数的来源说明（参数来源为配置ID，临时参数为 "user"）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchArgsExplanation {
+    pub browser_id: String,
+    pub layers: Vec<LayerExplanation>,
+    pub resolved: LaunchArgs,
+}
+
+#[derive(Debug, Clone)]
+struct ListItem {
+    key: String,
+    raw: String,
+    source: String,
+}
+
+#[derive(Debug, Clone)]
+enum Entry {
+    Switch {
+        name: String,
+        value: Option<String>,
+        source: String,
+    },
+    List {
+        name: String,
+        items: Vec<ListItem>,
+    },
+    Positional {
+        value: String,
+        source: String,
+    },
+}
+
+/// 启动参数合并器
+///
+/// 按添加顺序确定优先级：后添加的来源覆盖先添加的来源。
+/// - 普通开关按名称去重，值不同时保留后者；
+/// - `--enable-features`/`--disable-features` 等列表型开关逐项合并，
+///   同一功能在启用和禁用之间冲突时以后者为准；
+/// - `--js-flags` 按 V8 参数名逐项合并。
+#[derive(Debug, Default)]
+pub struct LaunchArgsBuilder {
+    entries: Vec<Entry>,
+    overrides: Vec<ArgumentOverride>,
+    env: BTreeMap<String, (String, String)>, // 名称 -> (值, 来源)
+    working_dir: Option<(String, String)>,
+    variables: BTreeMap<String, (String, String)>,
+    firefox_prefs: BTreeMap<String, (serde_json::Value, String)>,
+    preferences: BTreeMap<String, (serde_json::Value, String)>,
+    local_state: BTreeMap<String, (serde_json::Value, String)>,
+    experiments: Vec<(String, String)>, // (实验条目, 来源)
+    policies: BTreeMap<String, (serde_json::Value, String)>,
+}
+
+impl LaunchArgsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加一组来自同一来源的参数
+    pub fn push_args<S: AsRef<str>>(&mut self, args: &[S], source: &str) {
+        for arg in args {
+            self.push(arg.as_ref(), source);
+        }
+    }
+
+    /// 添加单个参数
+    pub fn push(&mut self, arg: &str, source: &str) {
+        let arg = normalize_switch(arg.trim());
+        let arg = arg.as_str();
+        if arg.is_empty() {
+            return;
+        }
+
+        if !arg.starts_with("--") {
+            self.entries.push(Entry::Positional {
+                value: arg.to_string(),
+                source: source.to_string(),
+            });
+            return;
+        }
+
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (arg, None),
+        };
+
+        if let Some((_, opposite)) = FEATURE_LIST_SWITCHES.iter().find(|(s, _)| *s == name) {
+            for item in value.unwrap_or_default().split(',') {
+                self.push_feature(name, opposite, item.trim(), source);
+            }
+        } else if name == JS_FLAGS_SWITCH {
+            for item in value.unwrap_or_default().split_whitespace() {
+                let key = item.split('=').next().unwrap_or(item).replace('_', "-");
+                self.push_list_item(name, key, item, source);
+            }
+        } else {
+            self.push_switch(name, value, source);
+        }
+    }
+
+    /// 设置环境变量
+    pub fn set_env(&mut self, key: &str, value: &str, source: &str) {
+        let previous = self
+            .env
+            .insert(key.to_string(), (value.to_string(), source.to_string()));
+        if let Some((previous_value, previous_source)) = previous {
+            if previous_value != value {
+                self.record_override(format!("env:{}", key), previous_value, previous_source, value, source);
+            }
+        }
+    }
+
+    /// 设置工作目录
+    pub fn set_working_dir(&mut self, dir: &str, source: &str) {
+        let previous = self
+            .working_dir
+            .replace((dir.to_string(), source.to_string()));
+        if let Some((previous_dir, previous_source)) = previous {
+            if previous_dir != dir {
+                self.record_override("cwd".to_string(), previous_dir, previous_source, dir, source);
+            }
+        }
+    }
+
+    /// 设置自定义占位符变量
+    pub fn set_variable(&mut self, name: &str, value: &str, source: &str) {
+        let previous = self
+            .variables
+            .insert(name.to_string(), (value.to_string(), source.to_string()));
+        if let Some((previous_value, previous_source)) = previous {
+            if previous_value != value {
+                self.record_override(format!("var:{}", name), previous_value, previous_source, value, source);
+            }
+        }
+    }
+
+    /// 设置 Firefox 首选项
+    pub fn set_firefox_pref(&mut self, name: &str, value: &serde_json::Value, source: &str) {
+        let previous = self
+            .firefox_prefs
+            .insert(name.to_string(), (value.clone(), source.to_string()));
+        self.record_json_override("pref", name, previous, value, source);
+    }
+
+    /// 设置写入 Chrome Default/Preferences 的首选项
+    pub fn set_preference(&mut self, path: &str, value: &serde_json::Value, source: &str) {
+        let previous = self
+            .preferences
+            .insert(path.to_string(), (value.clone(), source.to_string()));
+        self.record_json_override("preference", path, previous, value, source);
+    }
+
+    /// 设置写入 Chrome Local State 的值
+    pub fn set_local_state(&mut self, path: &str, value: &serde_json::Value, source: &str) {
+        let previous = self
+            .local_state
+            .insert(path.to_string(), (value.clone(), source.to_string()));
+        self.record_json_override("local_state", path, previous, value, source);
+    }
+
+    /// 启用 chrome://flags 中的实验，同名实验的不同选项以后者为准
+    pub fn enable_experiment(&mut self, entry: &str, source: &str) {
+        let name = ProfileSeed::experiment_name(entry);
+        match self
+            .experiments
+            .iter_mut()
+            .find(|(existing, _)| ProfileSeed::experiment_name(existing) == name)
+        {
+            Some((existing, existing_source)) => {
+                if existing != entry {
+                    let previous = std::mem::replace(existing, entry.to_string());
+                    let previous_source = std::mem::replace(existing_source, source.to_string());
+                    self.record_override(format!("experiment:{}", name), previous, previous_source, entry, source);
+                } else {
+                    *existing_source = source.to_string();
+                }
+            }
+            None => self.experiments.push((entry.to_string(), source.to_string())),
+        }
+    }
+
+    /// 设置企业策略
+    pub fn set_policy(&mut self, name: &str, value: &serde_json::Value, source: &str) {
+        let previous = self
+            .policies
+            .insert(name.to_string(), (value.clone(), source.to_string()));
+        self.record_json_override("policy", name, previous, value, source);
+    }
+
+    fn record_json_override(
+        &mut self,
+        kind: &str,
+        name: &str,
+        previous: Option<(serde_json::Value, String)>,
+        value: &serde_json::Value,
+        source: &str,
+    ) {
+        if let Some((previous_value, previous_source)) = previous {
+            if &previous_value != value {
+                self.record_override(
+                    format!("{}:{}", kind, name),
+                    previous_value.to_string(),
+                    previous_source,
+                    &value.to_string(),
+                    source,
+                );
+            }
+        }
+    }
+
+    fn record_override(&mut self, switch: String, previous: String, previous_source: String, value: &str, source: &str) {
+        self.overrides.push(ArgumentOverride {
+            switch,
+            item: None,
+            previous_value: Some(previous),
+            previous_source,
+            new_value: Some(value.to_string()),
+            new_source: source.to_string(),
+        });
+    }
+
+    fn push_switch(&mut self, name: &str, value: Option<&str>, source: &str) {
+        let existing = self.entries.iter_mut().find_map(|entry| match entry {
+            Entry::Switch {
+                name: n,
+                value,
+                source,
+            } if n == name => Some((value, source)),
+            _ => None,
+        });
+
+        match existing {
+            Some((existing_value, existing_source)) => {
+                if existing_value.as_deref() != value {
+                    self.overrides.push(ArgumentOverride {
+                        switch: name.to_string(),
+                        item: None,
+                        previous_value: existing_value.clone(),
+                        previous_source: existing_source.clone(),
+                        new_value: value.map(str::to_string),
+                        new_source: source.to_string(),
+                    });
+                    *existing_value = value.map(str::to_string);
+                }
+                *existing_source = source.to_string();
+            }
+            None => self.entries.push(Entry::Switch {
+                name: name.to_string(),
+                value: value.map(str::to_string),
+                source: source.to_string(),
+            }),
+        }
+    }
+
+    fn push_feature(&mut self, name: &str, opposite: &str, raw: &str, source: &str) {
+        if raw.is_empty() {
+            return;
+        }
+        // 功能参数形如 "Feature:param/value" 或 "Feature<Trial"
+        let key = raw
+            .split([':', '<'])
+            .next()
+            .unwrap_or(raw)
+            .to_string();
+
+        // 对立开关中的同名功能被本次设置覆盖
+        if let Some(removed) = self.remove_list_item(opposite, &key) {
+            self.overrides.push(ArgumentOverride {
+                switch: name.to_string(),
+                item: Some(key.clone()),
+                previous_value: Some(format!("{}={}", opposite, removed.raw)),
+                previous_source: removed.source,
+                new_value: Some(format!("{}={}", name, raw)),
+                new_source: source.to_string(),
+            });
+        }
+
+        self.push_list_item(name, key, raw, source);
+    }
+
+    fn push_list_item(&mut self, name: &str, key: String, raw: &str, source: &str) {
+        let index = match self
+            .entries
+            .iter()
+            .position(|e| matches!(e, Entry::List { name: n, .. } if n == name))
+        {
+            Some(index) => index,
+            None => {
+                self.entries.push(Entry::List {
+                    name: name.to_string(),
+                    items: Vec::new(),
+                });
+                self.entries.len() - 1
+            }
+        };
+        let Entry::List { items, .. } = &mut self.entries[index] else {
+            return;
+        };
+
+        match items.iter_mut().find(|item| item.key == key) {
+            Some(item) => {
+                if item.raw != raw {
+                    self.overrides.push(ArgumentOverride {
+                        switch: name.to_string(),
+                        item: Some(key.clone()),
+                        previous_value: Some(item.raw.clone()),
+                        previous_source: item.source.clone(),
+                        new_value: Some(raw.to_string()),
+                        new_source: source.to_string(),
+                    });
+                    item.raw = raw.to_string();
+                }
+                item.source = source.to_string();
+            }
+            None => items.push(ListItem {
+                key,
+                raw: raw.to_string(),
+                source: source.to_string(),
+            }),
+        }
+    }
+
+    fn remove_list_item(&mut self, name: &str, key: &str) -> Option<ListItem> {
+        self.entries.iter_mut().find_map(|entry| match entry {
+            Entry::List { name: n, items } if n == name => items
+                .iter()
+                .position(|item| item.key == key)
+                .map(|index| items.remove(index)),
+            _ => None,
+        })
+    }
+
+    /// 生成最终参数（开关按首次出现的顺序排列，位置参数在最后）
+    pub fn build(self) -> LaunchArgs {
+        let mut switches = Vec::new();
+        let mut positionals = Vec::new();
+
+        for entry in self.entries {
+            match entry {
+                Entry::Switch {
+                    name,
+                    value,
+                    source,
+                } => switches.push(ResolvedArgument {
+                    argument: match &value {
+                        Some(value) => format!("{}={}", name, value),
+                        None => name.clone(),
+                    },
+                    switch: Some(name),
+                    sources: vec![source],
+                }),
+                Entry::List { name, items } => {
+                    if items.is_empty() {
+                        continue;
+                    }
+                    let separator = if name == JS_FLAGS_SWITCH { " " } else { "," };
+                    let value = items
+                        .iter()
+                        .map(|item| item.raw.as_str())
+                        .collect::<Vec<_>>()
+                        .join(separator);
+                    let mut sources: Vec<String> = Vec::new();
+                    for item in items {
+                        if !sources.contains(&item.source) {
+                            sources.push(item.source);
+                        }
+                    }
+                    switches.push(ResolvedArgument {
+                        argument: format!("{}={}", name, value),
+                        switch: Some(name),
+                        sources,
+                    });
+                }
+                Entry::Positional { value, source } => positionals.push(ResolvedArgument {
+                    argument: value,
+                    switch: None,
+                    sources: vec![source],
+                }),
+            }
+        }
+
+        let arguments: Vec<ResolvedArgument> = switches.into_iter().chain(positionals).collect();
+        LaunchArgs {
+            args: arguments.iter().map(|a| a.argument.clone()).collect(),
+            arguments,
+            overrides: self.overrides,
+            env: self.env.into_iter().map(|(key, (value, _))| (key, value)).collect(),
+            working_dir: self.working_dir.map(|(dir, _)| dir),
+            variables: self.variables.into_iter().map(|(key, (value, _))| (key, value)).collect(),
+            unresolved_placeholders: Vec::new(),
+            config_ids: Vec::new(),
+            firefox_prefs: self
+                .firefox_prefs
+                .into_iter()
+                .map(|(name, (value, _))| (name, value))
+                .collect(),
+            profile_seed: ProfileSeed {
+                preferences: self
+                    .preferences
+                    .into_iter()
+                    .map(|(path, (value, _))| (path, value))
+                    .collect(),
+                local_state: self
+                    .local_state
+                    .into_iter()
+                    .map(|(path, (value, _))| (path, value))
+                    .collect(),
+                enabled_experiments: self.experiments.into_iter().map(|(entry, _)| entry).collect(),
+            },
+            policies: self
+                .policies
+                .into_iter()
+                .map(|(name, (value, _))| (name, value))
+                .collect(),
+        }
+    }
+}
+
+impl LaunchArgs {
+    /// 参数、环境变量、工作目录、自定义变量和字符串类型的首选项中引用的所有占位符
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .args
+            .iter()
+            .chain(self.env.values())
+            .chain(self.working_dir.iter())
+            .chain(self.variables.values())
+            .chain(
+                self.firefox_prefs
+                    .values()
+                    .chain(self.profile_seed.preferences.values())
+                    .chain(self.profile_seed.local_state.values())
+                    .filter_map(|v| match v {
+                        serde_json::Value::String(text) => Some(text),
+                        _ => None,
+                    }),
+            )
+            .flat_map(|text| find_placeholders(text))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// 用内置变量和自定义变量替换占位符（自定义变量的值中可以引用内置变量）
+    pub fn substitute(&mut self, builtins: &BTreeMap<String, String>) {
+        let mut variables = builtins.clone();
+        let mut unresolved: Vec<String> = Vec::new();
+        let mut resolve = |text: &str, variables: &BTreeMap<String, String>| {
+            let (result, unknown) = substitute_placeholders(text, variables);
+            for name in unknown {
+                if !unresolved.contains(&name) {
+                    unresolved.push(name);
+                }
+            }
+            result
+        };
+
+        for (name, value) in &self.variables {
+            if !builtins.contains_key(name) {
+                let value = resolve(value, builtins);
+                variables.insert(name.clone(), value);
+            }
+        }
+
+        for arg in self.args.iter_mut() {
+            *arg = resolve(arg, &variables);
+        }
+        for argument in self.arguments.iter_mut() {
+            argument.argument = resolve(&argument.argument, &variables);
+        }
+        for value in self.env.values_mut() {
+            *value = resolve(value, &variables);
+        }
+        if let Some(dir) = self.working_dir.as_mut() {
+            *dir = resolve(dir, &variables);
+        }
+        for value in self
+            .firefox_prefs
+            .values_mut()
+            .chain(self.profile_seed.preferences.values_mut())
+            .chain(self.profile_seed.local_state.values_mut())
+        {
+            if let serde_json::Value::String(text) = value {
+                *text = resolve(text, &variables);
+            }
+        }
+
+        self.variables = variables;
+        self.unresolved_placeholders = unresolved;
+    }
+}
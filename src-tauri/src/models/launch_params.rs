@@ -1,4 +1,6 @@
+use super::{BrowserFamily, BrowserInfo, BrowserType, ProfileSeed, VersionRequirement};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// 浏览器启动参数配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,13 +9,40 @@ pub struct BrowserLaunchConfig {
     pub name: String,                    // 配置名称
     pub description: String,              // 配置描述
     pub browser_id: Option<String>,       // 关联的浏览器ID，None表示全局配置
+    #[serde(default)]
+    pub browser_type: Option<BrowserType>, // 适用的浏览器类型（未指定 browser_id 时生效）
+    #[serde(default)]
+    pub version_range: Option<VersionRequirement>, // 适用的浏览器版本范围（如 ">=120, <130"）
+    #[serde(default)]
+    pub extends: Option<String>,          // 继承的父配置ID，父配置的参数先于本配置应用
     pub parameters: Vec<LaunchParameter>, // 启动参数列表
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,    // 启动时设置的环境变量
+    #[serde(default)]
+    pub working_dir: Option<String>,      // 浏览器进程的工作目录
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>, // 自定义占位符变量，如 width -> "1280"
+    #[serde(default)]
+    pub firefox_prefs: BTreeMap<String, serde_json::Value>, // Firefox 首选项，如 "dom.webnotifications.enabled" -> false
+    #[serde(default)]
+    pub profile_seed: ProfileSeed, // 启动前写入 Chrome 用户数据目录的首选项和 Local State
+    #[serde(default)]
+    pub policy_set_ids: Vec<String>, // 应用的企业策略集，后面的策略集覆盖前面的同名策略
     pub is_enabled: bool,                // 是否启用
     pub is_default: bool,                // 是否为默认配置
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// 启动配置所在的层（按优先级从低到高排列）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum ConfigLayer {
+    Global,      // 全局配置
+    BrowserType, // 某一浏览器类型的配置
+    Install,     // 某个已安装浏览器的配置
+    AdHoc,       // 启动时临时指定的配置和参数
+}
+
 /// 单个启动参数
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LaunchParameter {
@@ -48,6 +77,8 @@ pub struct ParameterTemplate {
     pub name: String,
     pub description: String,
     pub category: TemplateCategory,
+    #[serde(default)]
+    pub browser_family: BrowserFamily, // 模板参数适用的浏览器内核
     pub parameters: Vec<LaunchParameter>,
     pub is_builtin: bool, // 是否为内置模板
 }
@@ -71,7 +102,16 @@ impl BrowserLaunchConfig {
             name,
             description,
             browser_id: None,
+            browser_type: None,
+            version_range: None,
+            extends: None,
             parameters: Vec::new(),
+            env: BTreeMap::new(),
+            working_dir: None,
+            variables: BTreeMap::new(),
+            firefox_prefs: BTreeMap::new(),
+            profile_seed: ProfileSeed::default(),
+            policy_set_ids: Vec::new(),
             is_enabled: true,
             is_default: false,
             created_at: now,
@@ -79,6 +119,40 @@ impl BrowserLaunchConfig {
         }
     }
 
+    /// 配置作为默认配置时所在的层
+    pub fn layer(&self) -> ConfigLayer {
+        if self.browser_id.is_some() {
+            ConfigLayer::Install
+        } else if self.browser_type.is_some() {
+            ConfigLayer::BrowserType
+        } else {
+            ConfigLayer::Global
+        }
+    }
+
+    /// 配置是否适用于指定的浏览器
+    pub fn applies_to(&self, browser: &BrowserInfo) -> bool {
+        let scope_matches = match (&self.browser_id, &self.browser_type) {
+            (Some(browser_id), _) => browser_id == &browser.id,
+            (None, Some(browser_type)) => browser_type == &browser.browser_type,
+            (None, None) => true,
+        };
+
+        scope_matches
+            && self
+                .version_range
+                .as_ref()
+                .map(|range| range.matches(&browser.version))
+                .unwrap_or(true)
+    }
+
+    /// 与另一配置是否处于同一作用域（同一作用域内只能有一个默认配置）
+    pub fn same_scope(&self, other: &BrowserLaunchConfig) -> bool {
+        self.browser_id == other.browser_id
+            && (self.browser_id.is_some()
+                || (self.browser_type == other.browser_type && self.version_range == other.version_range))
+    }
+
     /// 添加参数
     pub fn add_parameter(&mut self, parameter: LaunchParameter) {
         self.parameters.push(parameter);
@@ -105,17 +179,7 @@ impl BrowserLaunchConfig {
     pub fn to_command_args(&self) -> Vec<String> {
         self.get_enabled_parameters()
             .into_iter()
-            .flat_map(|param| {
-                if let Some(value) = &param.value {
-                    if value.is_empty() {
-                        vec![param.flag.clone()]
-                    } else {
-                        vec![param.flag.clone(), value.clone()]
-                    }
-                } else {
-                    vec![param.flag.clone()]
-                }
-            })
+            .map(|param| param.to_arg())
             .collect()
     }
 
@@ -139,6 +203,17 @@ impl LaunchParameter {
         }
     }
 
+    /// 转换为单个命令行参数
+    pub fn to_arg(&self) -> String {
+        match self.value.as_deref() {
+            // Chrome 只识别 "--flag=value" 形式的取值；开关已包含取值时忽略单独的取值
+            Some(value) if !value.is_empty() && !self.flag.contains('=') => {
+                format!("{}={}", self.flag, value)
+            }
+            _ => self.flag.clone(),
+        }
+    }
+
     pub fn with_value(mut self, value: String) -> Self {
         self.value = Some(value);
         self
@@ -165,15 +240,23 @@ impl ParameterTemplate {
             Self::create_performance_template(),
             Self::create_automation_template(),
             Self::create_security_testing_template(),
+            Self::create_firefox_automation_template(),
+            Self::create_firefox_privacy_template(),
         ]
     }
 
+    /// 模板是否适用于指定类型的浏览器
+    pub fn applies_to(&self, browser_type: &BrowserType) -> bool {
+        self.browser_family == browser_type.family()
+    }
+
     fn create_testing_template() -> ParameterTemplate {
         ParameterTemplate {
             id: "testing".to_string(),
             name: "测试模式".to_string(),
             description: "适用于网站测试的浏览器配置".to_string(),
             category: TemplateCategory::Testing,
+            browser_family: BrowserFamily::Chromium,
             is_builtin: true,
             parameters: vec![
                 LaunchParameter::new(
@@ -209,6 +292,7 @@ impl ParameterTemplate {
             name: "开发调试".to_string(),
             description: "适用于Web开发的浏览器配置".to_string(),
             category: TemplateCategory::Development,
+            browser_family: BrowserFamily::Chromium,
             is_builtin: true,
             parameters: vec![
                 LaunchParameter::new(
@@ -217,12 +301,6 @@ impl ParameterTemplate {
                     ParameterCategory::Development,
                 ).with_description("自动为所有标签页打开开发者工具".to_string()),
                 
-                LaunchParameter::new(
-                    "禁用缓存".to_string(),
-                    "--disable-application-cache".to_string(),
-                    ParameterCategory::Development,
-                ).with_description("禁用应用程序缓存".to_string()),
-                
                 LaunchParameter::new(
                     "启用实验性Web功能".to_string(),
                     "--enable-experimental-web-platform-features".to_string(),
@@ -244,6 +322,7 @@ impl ParameterTemplate {
             name: "隐私保护".to_string(),
             description: "注重隐私保护的浏览器配置".to_string(),
             category: TemplateCategory::Privacy,
+            browser_family: BrowserFamily::Chromium,
             is_builtin: true,
             parameters: vec![
                 LaunchParameter::new(
@@ -252,12 +331,6 @@ impl ParameterTemplate {
                     ParameterCategory::Privacy,
                 ).with_description("以隐身模式启动浏览器".to_string()),
                 
-                LaunchParameter::new(
-                    "禁用地理位置".to_string(),
-                    "--disable-geolocation".to_string(),
-                    ParameterCategory::Privacy,
-                ).with_description("禁用地理位置API".to_string()),
-                
                 LaunchParameter::new(
                     "禁用通知".to_string(),
                     "--disable-notifications".to_string(),
@@ -279,25 +352,20 @@ impl ParameterTemplate {
             name: "性能优化".to_string(),
             description: "优化浏览器性能的配置".to_string(),
             category: TemplateCategory::Performance,
+            browser_family: BrowserFamily::Chromium,
             is_builtin: true,
             parameters: vec![
                 LaunchParameter::new(
                     "禁用图片".to_string(),
-                    "--disable-images".to_string(),
+                    "--blink-settings=imagesEnabled=false".to_string(),
                     ParameterCategory::Performance,
                 ).with_description("禁用图片加载以提高性能".to_string()),
                 
                 LaunchParameter::new(
-                    "最大内存使用".to_string(),
-                    "--max_old_space_size".to_string(),
+                    "JS堆内存上限".to_string(),
+                    "--js-flags=--max-old-space-size=1024".to_string(),
                     ParameterCategory::Performance,
-                ).with_value("1024".to_string()).with_description("设置最大内存使用量(MB)".to_string()),
-                
-                LaunchParameter::new(
-                    "禁用插件".to_string(),
-                    "--disable-plugins".to_string(),
-                    ParameterCategory::Performance,
-                ).with_description("禁用所有插件".to_string()),
+                ).with_description("设置V8老生代堆内存上限(MB)".to_string()),
                 
                 LaunchParameter::new(
                     "GPU加速".to_string(),
@@ -314,6 +382,7 @@ impl ParameterTemplate {
             name: "自动化测试".to_string(),
             description: "适用于自动化测试的浏览器配置".to_string(),
             category: TemplateCategory::Automation,
+            browser_family: BrowserFamily::Chromium,
             is_builtin: true,
             parameters: vec![
                 LaunchParameter::new(
@@ -349,25 +418,26 @@ impl ParameterTemplate {
             name: "安全测试".to_string(),
             description: "用于安全测试的浏览器配置".to_string(),
             category: TemplateCategory::Security,
+            browser_family: BrowserFamily::Chromium,
             is_builtin: true,
             parameters: vec![
                 LaunchParameter::new(
-                    "禁用XSS审计".to_string(),
-                    "--disable-xss-auditor".to_string(),
+                    "禁用站点隔离".to_string(),
+                    "--disable-site-isolation-trials".to_string(),
                     ParameterCategory::Security,
-                ).with_description("禁用XSS保护机制".to_string()).dangerous(),
+                ).with_description("关闭站点隔离，跨站页面可共享渲染进程".to_string()).dangerous(),
                 
                 LaunchParameter::new(
-                    "允许所有来源".to_string(),
-                    "--disable-features=VizDisplayCompositor".to_string(),
+                    "允许本地文件互访".to_string(),
+                    "--allow-file-access-from-files".to_string(),
                     ParameterCategory::Security,
-                ).with_description("允许来自所有来源的请求".to_string()).dangerous(),
+                ).with_description("允许 file:// 页面读取其他本地文件".to_string()).dangerous(),
                 
                 LaunchParameter::new(
-                    "忽略SSL错误".to_string(),
-                    "--ignore-ssl-errors".to_string(),
+                    "忽略证书错误".to_string(),
+                    "--ignore-certificate-errors".to_string(),
                     ParameterCategory::Security,
-                ).with_description("忽略所有SSL错误".to_string()).dangerous(),
+                ).with_description("忽略所有SSL证书错误".to_string()).dangerous(),
                 
                 LaunchParameter::new(
                     "禁用同源策略".to_string(),
@@ -377,6 +447,60 @@ impl ParameterTemplate {
             ],
         }
     }
+
+    fn create_firefox_automation_template() -> ParameterTemplate {
+        ParameterTemplate {
+            id: "firefox_automation".to_string(),
+            name: "Firefox 自动化测试".to_string(),
+            description: "通过 WebDriver BiDi 控制 Firefox 的配置".to_string(),
+            category: TemplateCategory::Automation,
+            browser_family: BrowserFamily::Firefox,
+            is_builtin: true,
+            parameters: vec![
+                LaunchParameter::new(
+                    "无头模式".to_string(),
+                    "--headless".to_string(),
+                    ParameterCategory::Automation,
+                ).with_description("以无头模式运行（无界面）".to_string()),
+                
+                LaunchParameter::new(
+                    "远程调试端口".to_string(),
+                    "--remote-debugging-port".to_string(),
+                    ParameterCategory::Automation,
+                ).with_value("9222".to_string()).with_description("启用 WebDriver BiDi 并监听该端口".to_string()),
+                
+                LaunchParameter::new(
+                    "启用 Marionette".to_string(),
+                    "--marionette".to_string(),
+                    ParameterCategory::Automation,
+                ).with_description("启用 geckodriver 使用的 Marionette 协议".to_string()),
+            ],
+        }
+    }
+
+    fn create_firefox_privacy_template() -> ParameterTemplate {
+        ParameterTemplate {
+            id: "firefox_privacy".to_string(),
+            name: "Firefox 隐私保护".to_string(),
+            description: "以隐私浏览方式启动 Firefox".to_string(),
+            category: TemplateCategory::Privacy,
+            browser_family: BrowserFamily::Firefox,
+            is_builtin: true,
+            parameters: vec![
+                LaunchParameter::new(
+                    "隐私浏览".to_string(),
+                    "--private-window".to_string(),
+                    ParameterCategory::Privacy,
+                ).with_description("在隐私浏览窗口中打开".to_string()),
+                
+                LaunchParameter::new(
+                    "安全模式".to_string(),
+                    "--safe-mode".to_string(),
+                    ParameterCategory::Privacy,
+                ).with_description("禁用所有扩展和主题".to_string()),
+            ],
+        }
+    }
 }
 
 impl ParameterCategory {
@@ -2,17 +2,41 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+pub mod bundle;
+pub mod capture;
+pub mod chrome_policies;
+pub mod chrome_switches;
+pub mod config_document;
 pub mod error;
+pub mod launch;
+pub mod launch_args;
 pub mod launch_params;
+pub mod platform;
+pub mod profile_seed;
+pub mod repair;
+pub mod storage;
+pub mod version_req;
+pub use bundle::*;
+pub use capture::*;
+pub use chrome_policies::*;
+pub use chrome_switches::*;
+pub use config_document::*;
 pub use error::*;
+pub use launch::*;
+pub use launch_args::*;
 pub use launch_params::*;
+pub use platform::*;
+pub use profile_seed::*;
+pub use repair::*;
+pub use storage::*;
+pub use version_req::*;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BrowserInfo {
     pub id: String,
     pub browser_type: BrowserType,
     pub version: String,
-    pub platform: String,
+    pub platform: Platform,
     #[serde(serialize_with = "serialize_path", deserialize_with = "deserialize_path")]
     pub install_path: PathBuf,
     #[serde(serialize_with = "serialize_path", deserialize_with = "deserialize_path")]
@@ -20,6 +44,10 @@ pub struct BrowserInfo {
     pub download_date: DateTime<Utc>,
     pub file_size: u64,
     pub is_running: bool,
+    #[serde(default)]
+    pub is_external: bool, // 系统已安装的外部浏览器，不由本应用管理文件
+    #[serde(default)]
+    pub last_launched: Option<DateTime<Utc>>, // 最近一次启动时间，用于清理长期未使用的版本
 }
 
 fn serialize_path<S>(path: &PathBuf, serializer: S) -> Result<S::Ok, S::Error>
@@ -37,12 +65,162 @@ where
     Ok(PathBuf::from(s))
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum BrowserType {
     Chrome,
     Chromium,
     Firefox,
     ChromeDriver,
+    ChromeHeadlessShell,
+    FirefoxNightly,
+    FirefoxDevEdition,
+    FirefoxEsr,
+}
+
+impl BrowserType {
+    pub const ALL: [BrowserType; 8] = [
+        BrowserType::Chrome,
+        BrowserType::Chromium,
+        BrowserType::Firefox,
+        BrowserType::ChromeDriver,
+        BrowserType::ChromeHeadlessShell,
+        BrowserType::FirefoxNightly,
+        BrowserType::FirefoxDevEdition,
+        BrowserType::FirefoxEsr,
+    ];
+
+    /// 规范名称，用于命令参数和数据库存储
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BrowserType::Chrome => "chrome",
+            BrowserType::Chromium => "chromium",
+            BrowserType::Firefox => "firefox",
+            BrowserType::ChromeDriver => "chromedriver",
+            BrowserType::ChromeHeadlessShell => "chrome-headless-shell",
+            BrowserType::FirefoxNightly => "firefox-nightly",
+            BrowserType::FirefoxDevEdition => "firefox-devedition",
+            BrowserType::FirefoxEsr => "firefox-esr",
+        }
+    }
+
+    pub fn is_firefox(&self) -> bool {
+        self.firefox_channel().is_some()
+    }
+
+    /// 浏览器内核家族，决定启动参数和配置文件的格式
+    pub fn family(&self) -> BrowserFamily {
+        if self.is_firefox() {
+            BrowserFamily::Firefox
+        } else {
+            BrowserFamily::Chromium
+        }
+    }
+
+    /// Firefox 的发布渠道（下载工具的 build ID 以渠道名为前缀）
+    pub fn firefox_channel(&self) -> Option<&'static str> {
+        match self {
+            BrowserType::Firefox => Some("stable"),
+            BrowserType::FirefoxNightly => Some("nightly"),
+            BrowserType::FirefoxDevEdition => Some("devedition"),
+            BrowserType::FirefoxEsr => Some("esr"),
+            _ => None,
+        }
+    }
+
+    /// 下载工具使用的 build ID；Firefox 的版本别名（如 latest）解析为该渠道的最新版本
+    pub fn build_id(&self, version: &str) -> String {
+        match self.firefox_channel() {
+            Some(channel) if version.starts_with(|c: char| c.is_ascii_digit()) => {
+                format!("{}_{}", channel, version)
+            }
+            Some(channel) if !version.starts_with(&format!("{}_", channel)) => channel.to_string(),
+            _ => version.to_string(),
+        }
+    }
+
+    /// 去掉 build ID 中的渠道前缀，得到版本号
+    pub fn version_from_build_id(&self, build_id: &str) -> String {
+        self.firefox_channel()
+            .and_then(|channel| build_id.strip_prefix(&format!("{}_", channel)))
+            .unwrap_or(build_id)
+            .to_string()
+    }
+}
+
+impl std::fmt::Display for BrowserType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for BrowserType {
+    type Err = String;
+
+    /// 接受规范名称和枚举变体名（旧版本数据库中的写法），不区分大小写
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.to_ascii_lowercase().replace(['-', '_'], "");
+        BrowserType::ALL
+            .into_iter()
+            .find(|t| t.as_str().replace('-', "") == normalized)
+            .ok_or_else(|| format!("Unknown browser type: {}", s))
+    }
+}
+
+/// 浏览器内核家族
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BrowserFamily {
+    #[default]
+    Chromium, // Chrome、Chromium 及无头外壳
+    Firefox,  // 各渠道的 Firefox
+}
+
+impl BrowserFamily {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BrowserFamily::Chromium => "chromium",
+            BrowserFamily::Firefox => "firefox",
+        }
+    }
+
+    /// 指定用户数据目录（配置文件）的开关
+    pub fn profile_switch(&self) -> &'static str {
+        match self {
+            BrowserFamily::Chromium => "--user-data-dir",
+            BrowserFamily::Firefox => "--profile",
+        }
+    }
+}
+
+impl std::str::FromStr for BrowserFamily {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "chromium" => Ok(BrowserFamily::Chromium),
+            "firefox" => Ok(BrowserFamily::Firefox),
+            _ => Err(format!("Unknown browser family: {}", s)),
+        }
+    }
+}
+
+/// 扫描发现的系统已安装浏览器
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiscoveredBrowser {
+    pub browser_type: BrowserType,
+    pub version: String,
+    #[serde(serialize_with = "serialize_path", deserialize_with = "deserialize_path")]
+    pub executable_path: PathBuf,
+    pub source: DiscoverySource,
+    pub is_registered: bool,
+}
+
+/// 系统浏览器的发现来源
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum DiscoverySource {
+    Path,           // PATH 环境变量
+    SystemLocation, // 常见安装位置
+    Snap,           // Snap 包
+    Flatpak,        // Flatpak 应用
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -67,17 +245,36 @@ pub enum DownloadStatus {
     Retrying,
 }
 
+impl DownloadStatus {
+    /// 任务尚未结束
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self,
+            DownloadStatus::Pending | DownloadStatus::Downloading | DownloadStatus::Retrying
+        )
+    }
+}
+
+/// 安装请求的处理结果，同一构建不会重复下载
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum InstallOutcome {
+    Started { task_id: String },
+    InProgress { task_id: String }, // 已有相同构建的下载任务
+    Installed { browser_id: String }, // 相同构建已经安装
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SystemInfo {
-    pub platform: String,
+    pub platform: Option<Platform>, // 不支持的系统为空，此时只能下载其他平台的构建
     pub arch: String,
     pub available_versions: Vec<ChromeVersion>,
+    pub supported_platforms: Vec<Platform>, // 可以下载的目标平台
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ChromeVersion {
     pub version: String,
-    pub platform: String,
+    pub platform: Platform,
     pub download_url: Option<String>,
     pub file_size: Option<u64>,
     pub release_date: Option<DateTime<Utc>>,
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// 浏览器构建的目标平台
+///
+/// 可以下载任意平台的构建（如在 Linux 上准备 Windows 离线包），
+/// 但只有当前机器能运行的构建才允许启动。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Platform {
+    Linux64,
+    LinuxArm64,
+    MacX64,
+    MacArm64,
+    Win32,
+    Win64,
+}
+
+impl Platform {
+    pub const ALL: [Platform; 6] = [
+        Platform::Linux64,
+        Platform::LinuxArm64,
+        Platform::MacX64,
+        Platform::MacArm64,
+        Platform::Win32,
+        Platform::Win64,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Platform::Linux64 => "linux64",
+            Platform::LinuxArm64 => "linux-arm64",
+            Platform::MacX64 => "mac-x64",
+            Platform::MacArm64 => "mac-arm64",
+            Platform::Win32 => "win32",
+            Platform::Win64 => "win64",
+        }
+    }
+
+    /// 当前机器的平台，不支持的系统或架构返回 None
+    pub fn current() -> Option<Platform> {
+        if cfg!(target_os = "windows") {
+            if cfg!(target_arch = "x86_64") {
+                Some(Platform::Win64)
+            } else if cfg!(target_arch = "x86") {
+                Some(Platform::Win32)
+            } else {
+                None
+            }
+        } else if cfg!(target_os = "macos") {
+            if cfg!(target_arch = "aarch64") {
+                Some(Platform::MacArm64)
+            } else {
+                Some(Platform::MacX64)
+            }
+        } else if cfg!(target_os = "linux") {
+            if cfg!(target_arch = "x86_64") {
+                Some(Platform::Linux64)
+            } else if cfg!(target_arch = "aarch64") {
+                Some(Platform::LinuxArm64)
+            } else {
+                None
+            }
+        } else {
+            None
+        }
+    }
+
+    /// 当前机器能否运行该平台的构建（包括 64 位 Windows 运行 32 位构建、Rosetta 运行 x64 构建）
+    pub fn is_runnable(&self) -> bool {
+        match Platform::current() {
+            Some(host) => {
+                host == *self
+                    || matches!(
+                        (host, self),
+                        (Platform::Win64, Platform::Win32) | (Platform::MacArm64, Platform::MacX64)
+                    )
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_windows(&self) -> bool {
+        matches!(self, Platform::Win32 | Platform::Win64)
+    }
+
+    pub fn is_mac(&self) -> bool {
+        matches!(self, Platform::MacX64 | Platform::MacArm64)
+    }
+
+    /// 下载脚本（@puppeteer/browsers）使用的平台名
+    pub fn download_key(&self) -> &'static str {
+        match self {
+            Platform::Linux64 => "linux",
+            Platform::LinuxArm64 => "linux_arm",
+            Platform::MacX64 => "mac",
+            Platform::MacArm64 => "mac_arm",
+            Platform::Win32 => "win32",
+            Platform::Win64 => "win64",
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Platform {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // 兼容旧版本使用的 mac_arm、mac_x64、linux 等写法
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "linux64" | "linux" => Ok(Platform::Linux64),
+            "linux-arm64" | "linux-arm" => Ok(Platform::LinuxArm64),
+            "mac-x64" | "mac" => Ok(Platform::MacX64),
+            "mac-arm64" | "mac-arm" => Ok(Platform::MacArm64),
+            "win32" => Ok(Platform::Win32),
+            "win64" => Ok(Platform::Win64),
+            _ => Err(format!("Unknown platform: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for Platform {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Platform> for String {
+    fn from(platform: Platform) -> Self {
+        platform.to_string()
+    }
+}
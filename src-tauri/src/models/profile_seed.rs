@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 启动前写入 Chrome 用户数据目录的初始设置
+///
+/// 键为以点分隔的首选项路径（如 "download.default_directory"），
+/// 值为对象时与已有的设置逐层合并，其他类型直接替换。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProfileSeed {
+    #[serde(default)]
+    pub preferences: BTreeMap<String, serde_json::Value>, // 写入 Default/Preferences
+    #[serde(default)]
+    pub local_state: BTreeMap<String, serde_json::Value>, // 写入 Local State
+    #[serde(default)]
+    pub enabled_experiments: Vec<String>, // chrome://flags 中启用的实验，如 "smooth-scrolling@2"
+}
+
+impl ProfileSeed {
+    pub fn is_empty(&self) -> bool {
+        self.preferences.is_empty() && self.local_state.is_empty() && self.enabled_experiments.is_empty()
+    }
+
+    /// 实验条目的名称（去掉 "@选项序号" 后缀）
+    pub fn experiment_name(entry: &str) -> &str {
+        entry.split('@').next().unwrap_or(entry)
+    }
+
+    /// 检查首选项路径和实验条目的格式
+    pub fn validate(&self) -> Result<(), String> {
+        for path in self.preferences.keys().chain(self.local_state.keys()) {
+            if path.split('.').any(|segment| segment.trim().is_empty()) {
+                return Err(format!("Invalid preference path: {}", path));
+            }
+        }
+        for entry in &self.enabled_experiments {
+            let valid_option = match entry.split_once('@') {
+                Some((_, option)) => option.parse::<u32>().is_ok(),
+                None => true,
+            };
+            if Self::experiment_name(entry).is_empty()
+                || !valid_option
+                || entry.contains(|c: char| c == ',' || c.is_whitespace())
+            {
+                return Err(format!("Invalid experiment entry: {}", entry));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+/// 数据库与浏览器目录不一致的类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum RepairIssueKind {
+    StaleRecord,       // 记录指向的目录或可执行文件已不存在
+    WrongExecutable,   // 安装目录存在但可执行文件路径错误
+    OrphanDirectory,   // 目录中存在浏览器但没有对应记录
+}
+
+/// 孤立目录的处理方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum OrphanAction {
+    #[default]
+    Report,
+    Register,
+    Delete,
+}
+
+/// 一致性修复选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairOptions {
+    #[serde(default)]
+    pub dry_run: bool, // 只检查不修复
+    #[serde(default = "default_true")]
+    pub remove_stale_records: bool,
+    #[serde(default = "default_true")]
+    pub fix_executables: bool,
+    #[serde(default)]
+    pub orphan_action: OrphanAction,
+}
+
+impl Default for RepairOptions {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            remove_stale_records: true,
+            fix_executables: true,
+            orphan_action: OrphanAction::Report,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 发现的问题及处理结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairIssue {
+    pub kind: RepairIssueKind,
+    pub browser_id: Option<String>, // 孤立目录注册后为新记录的ID
+    pub path: String,
+    pub detail: String,
+    pub fixed: bool,
+    pub action: Option<String>, // 实际执行的修复
+    pub error: Option<String>,
+}
+
+/// 一致性检查和修复报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairReport {
+    pub dry_run: bool,
+    pub scanned_records: usize,
+    pub scanned_directories: usize,
+    pub issues: Vec<RepairIssue>,
+    pub fixed_count: usize,
+}
@@ -0,0 +1,103 @@
+use crate::models::{BrowserInfo, BrowserType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 单个浏览器安装占用的磁盘空间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserStorage {
+    pub browser_id: String,
+    pub browser_type: BrowserType,
+    pub version: String,
+    pub install_path: String,
+    pub size_bytes: u64,
+    pub is_external: bool, // 外部浏览器不计入总占用
+    pub last_launched: Option<DateTime<Utc>>,
+}
+
+/// 应用数据的磁盘占用情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageUsage {
+    pub browsers: Vec<BrowserStorage>,
+    pub browsers_bytes: u64,
+    pub profiles_bytes: u64,
+    pub logs_bytes: u64,
+    pub artifacts_bytes: u64,
+    pub trash_bytes: u64, // 回收站中尚未清除的浏览器
+    pub total_bytes: u64,
+}
+
+/// 浏览器版本保留策略，未设置的规则不生效
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    #[serde(default)]
+    pub keep_latest_per_type: Option<usize>, // 每种浏览器类型在每个平台上保留的最新版本数
+    #[serde(default)]
+    pub max_total_gb: Option<f64>, // 浏览器总占用上限（含正在运行的版本），超出时先删除最久未使用的版本
+    #[serde(default)]
+    pub max_unused_days: Option<u32>, // 超过天数未启动的版本（从未启动时按下载时间计算）
+}
+
+/// 按保留策略将被删除的浏览器
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionCandidate {
+    pub browser_id: String,
+    pub browser_type: BrowserType,
+    pub version: String,
+    pub size_bytes: u64,
+    pub last_used: DateTime<Utc>,
+    pub reasons: Vec<String>,
+}
+
+/// 保留策略执行结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    pub policy: RetentionPolicy,
+    pub candidates: Vec<RetentionCandidate>,
+    pub deleted: Vec<String>,
+    pub failed: Vec<RetentionFailure>,
+    pub skipped_running: Vec<String>, // 正在运行而未删除的浏览器
+    pub freed_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionFailure {
+    pub browser_id: String,
+    pub error: String,
+}
+
+/// 删除浏览器的选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BrowserDeleteOptions {
+    #[serde(default)]
+    pub stop_running: bool, // 先停止正在运行的进程，否则拒绝删除
+    #[serde(default)]
+    pub permanent: bool, // 直接删除而不移入回收站
+}
+
+/// 回收站中的浏览器
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub id: String,
+    pub browser: BrowserInfo, // 删除前的记录，恢复时原样写回
+    pub trash_path: String,
+    pub size_bytes: u64,
+    pub trashed_at: DateTime<Utc>,
+}
+
+/// 清空浏览器的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClearBrowsersReport {
+    pub trashed: Vec<String>,
+    pub stopped: Vec<String>,          // 删除前被停止的浏览器
+    pub skipped_external: Vec<String>, // 外部浏览器不会被删除
+    pub total_bytes: u64,
+}
+
+/// 回收站清理结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashPurgeReport {
+    pub purged: Vec<String>, // 回收站条目ID
+    pub freed_bytes: u64,
+    pub errors: Vec<String>,
+}
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// 浏览器版本约束，如 ">=120, <130"、"^120"、"~120.0"、"120"
+///
+/// 版本按数字段逐段比较（缺省段视为 0），适用于 Chrome 的四段版本号
+/// 以及 Firefox 的 "128.0esr" 等带后缀的版本号。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct VersionRequirement {
+    comparators: Vec<Comparator>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Comparator {
+    op: VersionOp,
+    version: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VersionOp {
+    Exact,        // "=120.0" 或 "120"：给出的各段全部相同
+    Greater,      // ">"
+    GreaterEq,    // ">="
+    Less,         // "<"
+    LessEq,       // "<="
+    Caret,        // "^120.1"：主版本相同且不低于给定版本
+    Tilde,        // "~120.1"：前两段相同且不低于给定版本
+}
+
+impl VersionRequirement {
+    pub fn parse(requirement: &str) -> Result<Self, String> {
+        // 允许运算符与版本号之间有空格（如 ">= 120"）
+        let mut normalized = String::with_capacity(requirement.len());
+        for c in requirement.chars() {
+            if c.is_whitespace() && normalized.ends_with(['<', '>', '=', '^', '~']) {
+                continue;
+            }
+            normalized.push(c);
+        }
+
+        let comparators = normalized
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|part| !part.is_empty() && *part != "*")
+            .map(Comparator::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { comparators })
+    }
+
+    /// 版本是否满足全部约束（无法解析的版本号不满足任何非空约束）
+    pub fn matches(&self, version: &str) -> bool {
+        if self.comparators.is_empty() {
+            return true;
+        }
+        match parse_version(version) {
+            Some(version) => self.comparators.iter().all(|c| c.matches(&version)),
+            None => false,
+        }
+    }
+}
+
+impl Comparator {
+    fn parse(part: &str) -> Result<Self, String> {
+        let (op, rest) = [
+            (">=", VersionOp::GreaterEq),
+            ("<=", VersionOp::LessEq),
+            (">", VersionOp::Greater),
+            ("<", VersionOp::Less),
+            ("=", VersionOp::Exact),
+            ("^", VersionOp::Caret),
+            ("~", VersionOp::Tilde),
+        ]
+        .iter()
+        .find_map(|(prefix, op)| part.strip_prefix(prefix).map(|rest| (*op, rest)))
+        .unwrap_or((VersionOp::Exact, part));
+
+        let rest = rest.trim_end_matches(".*").trim_end_matches(".x");
+        let version = parse_version(rest)
+            .ok_or_else(|| format!("Invalid version requirement: {}", part))?;
+
+        Ok(Self { op, version })
+    }
+
+    fn matches(&self, version: &[u64]) -> bool {
+        let ordering = compare_versions(version, &self.version);
+        match self.op {
+            VersionOp::Exact => self.prefix_matches(version, self.version.len()),
+            VersionOp::Greater => ordering == Ordering::Greater,
+            VersionOp::GreaterEq => ordering != Ordering::Less,
+            VersionOp::Less => ordering == Ordering::Less,
+            VersionOp::LessEq => ordering != Ordering::Greater,
+            VersionOp::Caret => ordering != Ordering::Less && self.prefix_matches(version, 1),
+            VersionOp::Tilde => {
+                ordering != Ordering::Less && self.prefix_matches(version, self.version.len().min(2))
+            }
+        }
+    }
+
+    fn prefix_matches(&self, version: &[u64], segments: usize) -> bool {
+        (0..segments).all(|i| version.get(i).copied().unwrap_or(0) == self.version[i])
+    }
+}
+
+/// 解析版本号中的数字段（如 "128.0esr" -> [128, 0]）
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let mut segments = Vec::new();
+    for part in version.trim().split('.') {
+        let digits: String = part.chars().take_while(|c| c.is_ascii_digit()).collect();
+        if digits.is_empty() {
+            break;
+        }
+        segments.push(digits.parse().ok()?);
+        if digits.len() != part.len() {
+            break;
+        }
+    }
+    (!segments.is_empty()).then_some(segments)
+}
+
+/// 按数字段比较两个版本号，无法解析的版本号排在最前
+pub fn compare_version_strings(a: &str, b: &str) -> Ordering {
+    match (parse_version(a), parse_version(b)) {
+        (Some(a), Some(b)) => compare_versions(&a, &b),
+        (a, b) => a.is_some().cmp(&b.is_some()),
+    }
+}
+
+fn compare_versions(a: &[u64], b: &[u64]) -> Ordering {
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| a.get(i).copied().unwrap_or(0).cmp(&b.get(i).copied().unwrap_or(0)))
+        .find(|ordering| *ordering != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+impl fmt::Display for VersionRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.comparators.is_empty() {
+            return write!(f, "*");
+        }
+        let parts: Vec<String> = self
+            .comparators
+            .iter()
+            .map(|c| {
+                let op = match c.op {
+                    VersionOp::Exact => "=",
+                    VersionOp::Greater => ">",
+                    VersionOp::GreaterEq => ">=",
+                    VersionOp::Less => "<",
+                    VersionOp::LessEq => "<=",
+                    VersionOp::Caret => "^",
+                    VersionOp::Tilde => "~",
+                };
+                let version: Vec<String> = c.version.iter().map(u64::to_string).collect();
+                format!("{}{}", op, version.join("."))
+            })
+            .collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+impl TryFrom<String> for VersionRequirement {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<VersionRequirement> for String {
+    fn from(requirement: VersionRequirement) -> Self {
+        requirement.to_string()
+    }
+}
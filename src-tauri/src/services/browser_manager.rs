@@ -1,20 +1,51 @@
 use crate::database::Database;
-use crate::models::{BrowserInfo, BrowserType};
+use crate::models::{
+    BrowserFamily, BrowserInfo, BrowserLaunch, BrowserType, PolicyDelivery, DiscoveredBrowser, LaunchArgsBuilder, LaunchLogStream,
+    BrowserDeleteOptions, ClearBrowsersReport, InstallOutcome, LaunchOptions, Platform, LaunchRecord, TrashEntry,
+    TrashPurgeReport,
+};
 use crate::services::download_manager::DownloadManager;
-use std::path::PathBuf;
+use crate::services::security_policy::SecurityPolicyEngine;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::sync::Arc;
 use tokio::process::Command;
+use tokio::sync::{oneshot, Mutex, RwLock};
+
+/// 保留的启动日志数量
+const MAX_LAUNCH_LOGS: usize = 100;
+/// 回收站中的浏览器保留天数
+pub const TRASH_RETENTION_DAYS: i64 = 7;
+/// 停止浏览器后等待进程退出的最长时间
+const STOP_WAIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// 正在运行的浏览器进程
+struct RunningLaunch {
+    browser_id: String,
+    stop: Option<oneshot::Sender<()>>, // 已请求停止时为空
+}
 
 pub struct BrowserManager {
     database: Arc<Database>,
     download_manager: Arc<DownloadManager>,
+    security_policy: Arc<SecurityPolicyEngine>,
+    running_launches: Arc<RwLock<HashMap<String, RunningLaunch>>>,
+    install_lock: Mutex<()>,
 }
 
 impl BrowserManager {
-    pub fn new(database: Arc<Database>, download_manager: Arc<DownloadManager>) -> Self {
+    pub fn new(
+        database: Arc<Database>,
+        download_manager: Arc<DownloadManager>,
+        security_policy: Arc<SecurityPolicyEngine>,
+    ) -> Self {
         Self {
             database,
             download_manager,
+            security_policy,
+            running_launches: Arc::new(RwLock::new(HashMap::new())),
+            install_lock: Mutex::new(()),
         }
     }
 
@@ -25,12 +56,65 @@ impl BrowserManager {
             .map_err(|e| format!("Failed to get browsers: {}", e))
     }
 
+    /// 安装指定构建；已安装或正在下载时返回已有的记录或任务
+    ///
+    /// 可以下载其他平台的构建用于导出安装包，但这类构建不能在本机启动。
     pub async fn install_browser(
         &self,
         browser_type: BrowserType,
         version: &str,
-        platform: &str,
-    ) -> Result<String, String> {
+        platform: Platform,
+    ) -> Result<InstallOutcome, String> {
+        // 版本别名（包括 "131" 这样的主版本号）需要先解析，否则无法与已安装的构建比较；
+        // 无法解析的版本按原样使用
+        let resolved_version = match self
+            .download_manager
+            .resolve_build_id(&browser_type, version, platform)
+            .await
+        {
+            Ok(build_id) => build_id,
+            Err(e) => {
+                tracing::warn!("Failed to resolve version {}: {}", version, e);
+                version.to_string()
+            }
+        };
+
+        // 检查和创建任务需要串行，避免并发请求重复下载
+        let _guard = self.install_lock.lock().await;
+
+        if let Some(existing) = self
+            .find_installed_build(&browser_type, &resolved_version, platform)
+            .await?
+        {
+            if existing.executable_path.exists() {
+                tracing::info!(
+                    "{:?} {} ({}) is already installed as {}",
+                    browser_type,
+                    resolved_version,
+                    platform,
+                    existing.id
+                );
+                return Ok(InstallOutcome::Installed {
+                    browser_id: existing.id,
+                });
+            }
+
+            // 记录已失效，重新下载时替换
+            tracing::warn!("Removing stale record {} before reinstalling", existing.id);
+            self.database
+                .delete_browser(&existing.id)
+                .await
+                .map_err(|e| format!("Failed to delete browser from database: {}", e))?;
+        }
+
+        if let Some(task_id) = self
+            .download_manager
+            .find_active_task(&browser_type, &[version, resolved_version.as_str()], platform)
+            .await
+        {
+            return Ok(InstallOutcome::InProgress { task_id });
+        }
+
         // 生成唯一的下载任务ID
         let task_id = uuid::Uuid::new_v4().to_string();
 
@@ -38,13 +122,15 @@ impl BrowserManager {
         let browser_info = BrowserInfo {
             id: uuid::Uuid::new_v4().to_string(),
             browser_type,
-            version: version.to_string(),
-            platform: platform.to_string(),
+            version: resolved_version,
+            platform,
             install_path: PathBuf::new(), // 将在下载完成后填充
             executable_path: PathBuf::new(), // 将在下载完成后填充
             download_date: chrono::Utc::now(),
             file_size: 0, // 将在下载过程中更新
             is_running: false,
+            is_external: false,
+            last_launched: None,
         };
 
         // 启动下载任务
@@ -52,16 +138,280 @@ impl BrowserManager {
             .start_download(task_id.clone(), browser_info)
             .await?;
 
-        Ok(task_id)
+        Ok(InstallOutcome::Started { task_id })
+    }
+
+    /// 查找同一构建的受管理浏览器
+    pub(crate) async fn find_installed_build(
+        &self,
+        browser_type: &BrowserType,
+        version: &str,
+        platform: Platform,
+    ) -> Result<Option<BrowserInfo>, String> {
+        Ok(self.list_browsers().await?.into_iter().find(|b| {
+            !b.is_external
+                && b.browser_type == *browser_type
+                && b.version == version
+                && b.platform == platform
+        }))
+    }
+
+    /// 从本地归档文件（.zip / .tar.xz）或已解压的目录离线导入浏览器
+    pub async fn import_browser_archive(&self, source_path: &Path) -> Result<BrowserInfo, String> {
+        if !source_path.exists() {
+            return Err(format!("Source not found: {}", source_path.to_string_lossy()));
+        }
+
+        let browsers_dir = crate::utils::get_browsers_dir()?;
+        crate::utils::ensure_dir_exists(&browsers_dir).await?;
+
+        // 先解压/复制到临时目录，识别完成后再移动到最终位置
+        let staging_dir = browsers_dir.join(format!(".import-{}", uuid::Uuid::new_v4()));
+        crate::utils::ensure_dir_exists(&staging_dir).await?;
+
+        let result = self.import_from_staging(source_path, &staging_dir, &browsers_dir).await;
+
+        if staging_dir.exists() {
+            if let Err(e) = tokio::fs::remove_dir_all(&staging_dir).await {
+                tracing::warn!("Failed to remove import staging directory {:?}: {}", staging_dir, e);
+            }
+        }
+
+        result
+    }
+
+    async fn import_from_staging(
+        &self,
+        source_path: &Path,
+        staging_dir: &Path,
+        browsers_dir: &Path,
+    ) -> Result<BrowserInfo, String> {
+        if source_path.is_dir() {
+            crate::utils::copy_dir_recursive(source_path, staging_dir).await?;
+        } else {
+            crate::utils::extract_archive(source_path, staging_dir).await?;
+        }
+
+        let detected = crate::utils::detect_browser_in_dir(staging_dir).await?;
+
+        // 同一构建只能安装一份（下载的构建位于不同命名的目录中）
+        if let Some(existing) = self
+            .find_installed_build(&detected.browser_type, &detected.version, detected.platform)
+            .await?
+        {
+            return Err(format!(
+                "{:?} {} ({}) is already installed at {}",
+                detected.browser_type,
+                detected.version,
+                detected.platform,
+                existing.install_path.to_string_lossy()
+            ));
+        }
+
+        let install_path = browsers_dir
+            .join(crate::utils::get_browser_key(&detected.browser_type))
+            .join(format!("{}-{}", detected.platform, detected.version));
+        if install_path.exists() {
+            return Err(format!(
+                "{:?} {} ({}) is already installed at {}",
+                detected.browser_type,
+                detected.version,
+                detected.platform,
+                install_path.to_string_lossy()
+            ));
+        }
+
+        if let Some(parent) = install_path.parent() {
+            crate::utils::ensure_dir_exists(parent).await?;
+        }
+        tokio::fs::rename(staging_dir, &install_path)
+            .await
+            .map_err(|e| format!("Failed to move browser into place: {}", e))?;
+
+        // 优先使用标准位置查找，找不到时回退到识别出的可执行文件
+        let mut executable_path =
+            DownloadManager::find_executable(&install_path, &detected.browser_type, detected.platform);
+        if !executable_path.exists() {
+            let relative = detected
+                .executable_path
+                .strip_prefix(staging_dir)
+                .map_err(|_| "Detected executable is outside the import directory")?;
+            executable_path = install_path.join(relative);
+        }
+
+        let file_size = crate::utils::get_dir_size(&install_path).await.unwrap_or(0);
+
+        let browser_info = BrowserInfo {
+            id: uuid::Uuid::new_v4().to_string(),
+            browser_type: detected.browser_type,
+            version: detected.version,
+            platform: detected.platform,
+            install_path,
+            executable_path,
+            download_date: chrono::Utc::now(),
+            file_size,
+            is_running: false,
+            is_external: false,
+            last_launched: None,
+        };
+
+        self.database
+            .save_browser(&browser_info)
+            .await
+            .map_err(|e| format!("Failed to save browser to database: {}", e))?;
+
+        tracing::info!(
+            "Imported {:?} {} from {:?}",
+            browser_info.browser_type,
+            browser_info.version,
+            source_path
+        );
+        Ok(browser_info)
     }
 
-    pub async fn delete_browser(&self, browser_id: &str) -> Result<(), String> {
-        // 首先从数据库获取浏览器信息
+    /// 扫描系统中已安装的浏览器（PATH、常见安装位置、Snap/Flatpak）
+    pub async fn discover_system_browsers(&self) -> Result<Vec<DiscoveredBrowser>, String> {
+        let registered = self.list_browsers().await?;
+        let mut discovered = Vec::new();
+
+        for candidate in crate::utils::find_system_browser_candidates().await {
+            let Some(output) = crate::utils::run_version_command(&candidate.executable_path).await
+            else {
+                tracing::debug!("Skipping {:?}: --version failed", candidate.executable_path);
+                continue;
+            };
+
+            let (Some(browser_type), Some(version)) = (
+                crate::utils::classify_system_browser(&candidate.executable_path, &output),
+                crate::utils::parse_version_output(&output),
+            ) else {
+                tracing::debug!("Skipping {:?}: unrecognized output {:?}", candidate.executable_path, output);
+                continue;
+            };
+
+            let is_registered = registered
+                .iter()
+                .any(|b| Self::same_executable(&b.executable_path, &candidate.executable_path));
+
+            discovered.push(DiscoveredBrowser {
+                browser_type,
+                version,
+                executable_path: candidate.executable_path,
+                source: candidate.source,
+                is_registered,
+            });
+        }
+
+        tracing::info!("Discovered {} system browsers", discovered.len());
+        Ok(discovered)
+    }
+
+    /// 将系统已安装的浏览器注册为外部浏览器
+    pub async fn register_system_browser(&self, executable_path: &Path) -> Result<BrowserInfo, String> {
+        if !executable_path.is_file() {
+            return Err(format!(
+                "Browser executable not found at path: {}",
+                executable_path.to_string_lossy()
+            ));
+        }
+
+        // 符号链接或不同写法的路径可能指向同一个可执行文件，统一按实际路径比较和保存
+        let canonical_path = std::fs::canonicalize(executable_path)
+            .map_err(|e| format!("Failed to resolve browser path: {}", e))?;
         let browsers = self.list_browsers().await?;
-        let browser = browsers
+        if let Some(existing) = browsers
             .iter()
-            .find(|b| b.id == browser_id)
-            .ok_or("Browser not found")?;
+            .find(|b| Self::same_executable(&b.executable_path, &canonical_path))
+        {
+            return Err(format!(
+                "Browser is already registered at {}",
+                existing.executable_path.to_string_lossy()
+            ));
+        }
+
+        // 重新检测版本，不信任调用方传入的信息
+        let output = crate::utils::run_version_command(executable_path)
+            .await
+            .ok_or("Failed to run browser --version")?;
+        let browser_type = crate::utils::classify_system_browser(executable_path, &output)
+            .ok_or_else(|| format!("Unrecognized browser: {}", output))?;
+        let version = crate::utils::parse_version_output(&output)
+            .ok_or_else(|| format!("Unable to parse browser version from: {}", output))?;
+
+        let browser_info = BrowserInfo {
+            id: uuid::Uuid::new_v4().to_string(),
+            browser_type,
+            version,
+            platform: Platform::current().ok_or("Unsupported host platform")?,
+            install_path: canonical_path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+            executable_path: canonical_path,
+            download_date: chrono::Utc::now(),
+            file_size: 0,
+            is_running: false,
+            is_external: true,
+            last_launched: None,
+        };
+
+        self.database
+            .save_browser(&browser_info)
+            .await
+            .map_err(|e| format!("Failed to save browser to database: {}", e))?;
+
+        tracing::info!(
+            "Registered external {:?} {} at {:?}",
+            browser_info.browser_type,
+            browser_info.version,
+            browser_info.executable_path
+        );
+        Ok(browser_info)
+    }
+
+    /// 两个路径是否指向同一个可执行文件（无法解析时按原路径比较）
+    fn same_executable(a: &Path, b: &Path) -> bool {
+        let resolve = |path: &Path| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        resolve(a) == resolve(b)
+    }
+
+    /// 取消注册外部浏览器（仅删除记录，不删除文件）
+    pub async fn unregister_browser(&self, browser_id: &str) -> Result<(), String> {
+        let browser = self.get_browser_info(browser_id).await?;
+        if !browser.is_external {
+            return Err("Only external browsers can be unregistered".to_string());
+        }
+
+        self.database
+            .delete_browser(browser_id)
+            .await
+            .map_err(|e| format!("Failed to delete browser from database: {}", e))
+    }
+
+    pub async fn delete_browser(
+        &self,
+        browser_id: &str,
+        options: BrowserDeleteOptions,
+    ) -> Result<(), String> {
+        let browser = self.get_browser_info(browser_id).await?;
+
+        // 外部浏览器的文件不归本应用管理
+        if browser.is_external {
+            return Err("External browsers cannot be deleted, unregister them instead".to_string());
+        }
+
+        // 正在运行的浏览器不能删除，除非要求先停止
+        if self.is_browser_running(browser_id).await {
+            if !options.stop_running {
+                return Err("Browser is running, stop it before deleting".to_string());
+            }
+            self.stop_browser(browser_id).await?;
+        }
+
+        if !options.permanent {
+            self.move_to_trash(&[browser]).await?;
+            return Ok(());
+        }
 
         // 删除文件系统中的浏览器目录
         if browser.install_path.exists() {
@@ -79,17 +429,310 @@ impl BrowserManager {
         Ok(())
     }
 
+    /// 回收站中的浏览器（按删除时间倒序）
+    pub async fn list_trash(&self) -> Result<Vec<TrashEntry>, String> {
+        self.database
+            .get_trash_entries()
+            .await
+            .map_err(|e| format!("Failed to get trash entries: {}", e))
+    }
+
+    /// 从回收站恢复浏览器到原来的位置
+    pub async fn restore_from_trash(&self, entry_id: &str) -> Result<BrowserInfo, String> {
+        let entry = self
+            .list_trash()
+            .await?
+            .into_iter()
+            .find(|e| e.id == entry_id)
+            .ok_or("Trash entry not found")?;
+        let browser = &entry.browser;
+
+        if self.list_browsers().await?.iter().any(|b| b.id == browser.id) {
+            return Err("Browser record already exists".to_string());
+        }
+        if browser.install_path.exists() {
+            return Err(format!(
+                "Install directory already exists: {}",
+                browser.install_path.to_string_lossy()
+            ));
+        }
+
+        let trash_path = PathBuf::from(&entry.trash_path);
+        let moved = trash_path.exists();
+        if moved {
+            crate::utils::move_dir(&trash_path, &browser.install_path).await?;
+        }
+
+        if let Err(e) = self.database.restore_browser_from_trash(&entry).await {
+            if moved {
+                if let Err(e) = crate::utils::move_dir(&browser.install_path, &trash_path).await {
+                    tracing::warn!("Failed to move browser back to trash: {}", e);
+                }
+            }
+            return Err(format!("Failed to restore browser record: {}", e));
+        }
+
+        tracing::info!("Restored browser {} from trash", browser.id);
+        Ok(entry.browser)
+    }
+
+    /// 永久删除回收站中早于指定时长的浏览器，未指定时清空回收站
+    pub async fn purge_trash(
+        &self,
+        older_than: Option<chrono::Duration>,
+    ) -> Result<TrashPurgeReport, String> {
+        let now = chrono::Utc::now();
+        let mut report = TrashPurgeReport {
+            purged: Vec::new(),
+            freed_bytes: 0,
+            errors: Vec::new(),
+        };
+
+        for entry in self.list_trash().await? {
+            if matches!(older_than, Some(age) if now - entry.trashed_at < age) {
+                continue;
+            }
+
+            let trash_path = PathBuf::from(&entry.trash_path);
+            if trash_path.exists() {
+                if let Err(e) = tokio::fs::remove_dir_all(&trash_path).await {
+                    report
+                        .errors
+                        .push(format!("Failed to remove trash entry {}: {}", entry.id, e));
+                    continue;
+                }
+            }
+            if let Err(e) = self.database.delete_trash_entry(&entry.id).await {
+                report
+                    .errors
+                    .push(format!("Failed to delete trash entry {}: {}", entry.id, e));
+                continue;
+            }
+
+            report.freed_bytes += entry.size_bytes;
+            report.purged.push(entry.id);
+        }
+
+        if !report.purged.is_empty() {
+            tracing::info!("Purged {} browser(s) from trash", report.purged.len());
+        }
+        Ok(report)
+    }
+
+    /// 将浏览器目录移入回收站；任何一步失败时撤销已移动的目录，数据库记录保持不变
+    async fn move_to_trash(&self, browsers: &[BrowserInfo]) -> Result<Vec<TrashEntry>, String> {
+        let trash_dir = crate::utils::get_trash_dir()?;
+        let mut moved: Vec<(PathBuf, PathBuf)> = Vec::new();
+        let mut entries = Vec::with_capacity(browsers.len());
+
+        for browser in browsers {
+            let id = uuid::Uuid::new_v4().to_string();
+            let trash_path = trash_dir.join(&id);
+            let size_bytes = crate::utils::get_dir_size(&browser.install_path)
+                .await
+                .unwrap_or(browser.file_size);
+
+            if browser.install_path.exists() {
+                if let Err(e) = crate::utils::move_dir(&browser.install_path, &trash_path).await {
+                    Self::undo_trash_moves(&moved).await;
+                    return Err(format!(
+                        "Failed to move {:?} {} to trash: {}",
+                        browser.browser_type, browser.version, e
+                    ));
+                }
+                moved.push((browser.install_path.clone(), trash_path.clone()));
+            }
+
+            entries.push(TrashEntry {
+                id,
+                browser: browser.clone(),
+                trash_path: trash_path.to_string_lossy().to_string(),
+                size_bytes,
+                trashed_at: chrono::Utc::now(),
+            });
+        }
+
+        if let Err(e) = self.database.move_browsers_to_trash(&entries).await {
+            Self::undo_trash_moves(&moved).await;
+            return Err(format!("Failed to move browsers to trash: {}", e));
+        }
+
+        Ok(entries)
+    }
+
+    async fn undo_trash_moves(moved: &[(PathBuf, PathBuf)]) {
+        for (install_path, trash_path) in moved.iter().rev() {
+            if let Err(e) = crate::utils::move_dir(trash_path, install_path).await {
+                tracing::error!(
+                    "Failed to move {:?} back from trash {:?}: {}",
+                    install_path,
+                    trash_path,
+                    e
+                );
+            }
+        }
+    }
+
     pub async fn launch_browser(
         &self,
         browser_id: &str,
-        args: Option<Vec<String>>,
-    ) -> Result<(), String> {
+        options: LaunchOptions,
+    ) -> Result<BrowserLaunch, String> {
         // 获取浏览器信息
-        let browsers = self.list_browsers().await?;
-        let browser = browsers
-            .iter()
-            .find(|b| b.id == browser_id)
-            .ok_or("Browser not found")?;
+        let browser = self.get_browser_info(browser_id).await?;
+
+        // 每次启动的输出写入单独的日志目录
+        let logs_dir = crate::utils::get_launch_logs_dir()?;
+        crate::utils::ensure_dir_exists(&logs_dir).await?;
+        // 仍在运行的启动正在写入日志，不能删除
+        let running: Vec<String> = self.running_launches.read().await.keys().cloned().collect();
+        if let Err(e) = crate::utils::prune_old_dirs(&logs_dir, MAX_LAUNCH_LOGS - 1, &running).await {
+            tracing::warn!("Failed to prune old launch logs: {}", e);
+        }
+        let launch_id = uuid::Uuid::new_v4().to_string();
+        let log_dir = logs_dir.join(&launch_id);
+        crate::utils::ensure_dir_exists(&log_dir).await?;
+
+        let (mut cmd, args) = self.build_command(&browser, &options)?;
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        // 启动浏览器
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| format!("Failed to start browser: {}", e))?;
+
+        let pid = child.id();
+        let started_at = chrono::Utc::now();
+        tracing::info!("Browser {} started with PID: {:?}", browser_id, pid);
+
+        // 记录启动历史，用于之后重现本次启动
+        let profile_dir =
+            Self::profile_dir_arg(browser.browser_type.family(), &args).map(|dir| dir.to_string());
+        let record = LaunchRecord {
+            id: launch_id.clone(),
+            browser_id: browser_id.to_string(),
+            browser_version: browser.version.clone(),
+            executable_path: browser.executable_path.to_string_lossy().to_string(),
+            args,
+            env: options.env,
+            working_dir: options.working_dir,
+            config_ids: options.config_ids,
+            profile_dir,
+            pid,
+            started_at,
+            stopped_at: None,
+            exit_code: None,
+            log_dir: Some(log_dir.to_string_lossy().to_string()),
+            relaunch_of: options.relaunch_of,
+            firefox_prefs: options.firefox_prefs,
+            profile_seed: options.profile_seed,
+            policies: options.policies,
+        };
+        if let Err(e) = self.database.save_launch_record(&record).await {
+            tracing::warn!("Failed to save launch record {}: {}", launch_id, e);
+        }
+        if let Err(e) = self.database.set_browser_last_launched(browser_id, started_at).await {
+            tracing::warn!("Failed to update last launch time of browser {}: {}", browser_id, e);
+        }
+
+        spawn_log_pump(child.stdout.take(), log_dir.join(LaunchLogStream::Stdout.file_name()));
+        spawn_log_pump(child.stderr.take(), log_dir.join(LaunchLogStream::Stderr.file_name()));
+
+        let (stop_tx, stop_rx) = oneshot::channel();
+        self.running_launches.write().await.insert(
+            launch_id.clone(),
+            RunningLaunch {
+                browser_id: browser_id.to_string(),
+                stop: Some(stop_tx),
+            },
+        );
+
+        // 等待进程退出（或收到停止请求），避免产生僵尸进程
+        let browser_id_owned = browser_id.to_string();
+        let launch_id_owned = launch_id.clone();
+        let database = self.database.clone();
+        let running_launches = self.running_launches.clone();
+        tokio::spawn(async move {
+            let exited = tokio::select! {
+                status = child.wait() => Some(status),
+                _ = stop_rx => None,
+            };
+            let status = match exited {
+                Some(status) => status,
+                None => {
+                    tracing::info!("Stopping browser {} ({})", browser_id_owned, launch_id_owned);
+                    if let Err(e) = child.kill().await {
+                        tracing::warn!("Failed to kill browser {}: {}", browser_id_owned, e);
+                    }
+                    child.wait().await
+                }
+            };
+            running_launches.write().await.remove(&launch_id_owned);
+
+            let exit_code = match status {
+                Ok(status) => {
+                    tracing::info!("Browser {} exited with {}", browser_id_owned, status);
+                    status.code()
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to wait for browser {}: {}", browser_id_owned, e);
+                    None
+                }
+            };
+            if let Err(e) = database.finish_launch_record(&launch_id_owned, exit_code).await {
+                tracing::warn!("Failed to update launch record {}: {}", launch_id_owned, e);
+            }
+        });
+
+        Ok(BrowserLaunch {
+            launch_id,
+            browser_id: browser_id.to_string(),
+            pid,
+            started_at,
+            log_dir: log_dir.to_string_lossy().to_string(),
+        })
+    }
+
+    /// 运行一次性的无头浏览器进程并等待其退出，超时后强制结束
+    pub async fn run_headless(
+        &self,
+        browser_id: &str,
+        options: LaunchOptions,
+        timeout: std::time::Duration,
+    ) -> Result<std::process::Output, String> {
+        let browser = self.get_browser_info(browser_id).await?;
+        let (mut cmd, _) = self.build_command(&browser, &options)?;
+
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let child = cmd
+            .spawn()
+            .map_err(|e| format!("Failed to start browser: {}", e))?;
+
+        tokio::time::timeout(timeout, child.wait_with_output())
+            .await
+            .map_err(|_| format!("Browser did not exit within {} seconds", timeout.as_secs()))?
+            .map_err(|e| format!("Failed to wait for browser: {}", e))
+    }
+
+    /// 构建启动命令：合并默认参数，按安全策略检查，并应用环境变量和工作目录
+    fn build_command(
+        &self,
+        browser: &BrowserInfo,
+        options: &LaunchOptions,
+    ) -> Result<(Command, Vec<String>), String> {
+        // 其他平台的构建只用于导出
+        if !browser.platform.is_runnable() {
+            return Err(format!(
+                "{:?} {} is a {} build and cannot be launched on this machine",
+                browser.browser_type, browser.version, browser.platform
+            ));
+        }
 
         // 检查可执行文件是否存在
         if !browser.executable_path.exists() {
@@ -98,30 +741,248 @@ impl BrowserManager {
             return Err(format!("Browser executable not found at path: {}", path_str));
         }
 
-        // 构建启动命令
+        let policy_delivery = if options.policies.is_empty() {
+            None
+        } else {
+            Some(PolicyDelivery::for_browser(&browser.browser_type).ok_or_else(|| {
+                format!(
+                    "Enterprise policies are not supported for {} builds: only Chromium builds read policies from the --policy switch",
+                    browser.browser_type
+                )
+            })?)
+        };
+
         let mut cmd = Command::new(&browser.executable_path);
-        
-        // 添加默认参数
-        cmd.arg("--no-first-run")
-           .arg("--disable-default-apps");
+        let family = browser.browser_type.family();
 
-        // 添加用户指定的参数
-        if let Some(args) = args {
-            for arg in args {
-                cmd.arg(arg);
+        // 默认参数在前，用户指定的参数可以覆盖
+        let mut builder = LaunchArgsBuilder::new();
+        match family {
+            BrowserFamily::Chromium => {
+                builder.push_args(&["--no-first-run", "--disable-default-apps"], "default");
+                // 写入初始设置时不能修改默认用户数据目录，改用该浏览器专属的目录
+                if !options.profile_seed.is_empty() {
+                    let profile_dir = crate::utils::get_profiles_dir()?.join(&browser.id);
+                    builder.push(
+                        &format!("{}={}", family.profile_switch(), profile_dir.to_string_lossy()),
+                        "default",
+                    );
+                }
+            }
+            BrowserFamily::Firefox => {
+                // 不指定配置文件时使用该浏览器专属的目录，避免连接到已运行的实例。
+                // 与 Chromium 一致，默认不开启 --remote-debugging-port：远程调试端口允许本机任意进程
+                // 控制浏览器，需要时通过启动配置或 firefox_automation 模板显式开启。
+                let profile_dir = crate::utils::get_profiles_dir()?.join(&browser.id);
+                builder.push("--no-remote", "default");
+                builder.push(
+                    &format!("{}={}", family.profile_switch(), profile_dir.to_string_lossy()),
+                    "default",
+                );
             }
         }
+        builder.push_args(&options.args, "launch");
+        if policy_delivery == Some(PolicyDelivery::CommandLine) {
+            let policies_json = serde_json::to_string(&options.policies)
+                .map_err(|e| format!("Failed to serialize policies: {}", e))?;
+            builder.push(&format!("--policy={}", policies_json), "policy");
+        }
+        let args = builder.build().args;
 
-        // 启动浏览器
-        let child = cmd
-            .spawn()
-            .map_err(|e| format!("Failed to start browser: {}", e))?;
+        // 按安全策略检查最终参数
+        let validation = self.security_policy.evaluate(&args);
+        if validation.blocked {
+            return Err(format!(
+                "Launch blocked by security policy: {}",
+                validation.warnings.join("; ")
+            ));
+        }
+        if validation.requires_acknowledgement && !options.acknowledged {
+            return Err(format!(
+                "Launch requires acknowledgement of security risks: {}",
+                validation.warnings.join("; ")
+            ));
+        }
+        if !validation.is_safe {
+            tracing::warn!(
+                "Launching browser {} with security findings: {:?}",
+                browser.id,
+                validation.warnings
+            );
+        }
+
+        match family {
+            BrowserFamily::Chromium => {
+                if !options.firefox_prefs.is_empty() {
+                    tracing::warn!(
+                        "Ignoring {} Firefox preferences when launching {} browser {}",
+                        options.firefox_prefs.len(),
+                        browser.browser_type,
+                        browser.id
+                    );
+                }
+                if !options.profile_seed.is_empty() {
+                    let profile_dir = Self::profile_dir_arg(family, &args)
+                        .ok_or("Profile seeding requires --user-data-dir")?;
+                    crate::utils::seed_chrome_profile(Path::new(profile_dir), &options.profile_seed)?;
+                }
+                cmd.args(&args);
+            }
+            BrowserFamily::Firefox => {
+                if !options.profile_seed.is_empty() {
+                    tracing::warn!(
+                        "Ignoring Chrome profile seed when launching {} browser {}",
+                        browser.browser_type,
+                        browser.id
+                    );
+                }
+                let profile_dir = Self::profile_dir_arg(family, &args)
+                    .ok_or("Firefox profile directory is missing")?;
+                let mut prefs = crate::utils::default_firefox_prefs();
+                prefs.extend(options.firefox_prefs.clone());
+                crate::utils::write_user_js(Path::new(profile_dir), &prefs)?;
+                cmd.args(crate::utils::firefox_command_args(&args));
+            }
+        }
 
-        tracing::info!("Browser {} started with PID: {:?}", browser_id, child.id());
+        // 环境变量和工作目录
+        cmd.envs(&options.env);
+        if let Some(dir) = &options.working_dir {
+            if !Path::new(dir).is_dir() {
+                return Err(format!("Working directory not found: {}", dir));
+            }
+            cmd.current_dir(dir);
+        }
 
+        Ok((cmd, args))
+    }
+
+    /// 最终参数中指定的用户数据目录（配置文件目录）
+    fn profile_dir_arg(family: BrowserFamily, args: &[String]) -> Option<&str> {
+        let prefix = format!("{}=", family.profile_switch());
+        args.iter().rev().find_map(|arg| arg.strip_prefix(prefix.as_str()))
+    }
+
+    /// 停止一次启动的浏览器进程，返回进程是否仍在运行
+    pub async fn stop_launch(&self, launch_id: &str) -> Result<bool, String> {
+        let mut running_launches = self.running_launches.write().await;
+        match running_launches.get_mut(launch_id) {
+            Some(running) => {
+                // 进程退出后由等待任务移除记录
+                if let Some(stop) = running.stop.take() {
+                    let _ = stop.send(());
+                    tracing::info!("Stop requested for browser {} ({})", running.browser_id, launch_id);
+                }
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// 停止浏览器的所有进程并等待其退出
+    pub async fn stop_browser(&self, browser_id: &str) -> Result<(), String> {
+        let launch_ids: Vec<String> = self
+            .running_launches
+            .read()
+            .await
+            .iter()
+            .filter(|(_, running)| running.browser_id == browser_id)
+            .map(|(launch_id, _)| launch_id.clone())
+            .collect();
+        for launch_id in &launch_ids {
+            self.stop_launch(launch_id).await?;
+        }
+
+        let deadline = tokio::time::Instant::now() + STOP_WAIT_TIMEOUT;
+        while self.is_browser_running(browser_id).await {
+            if tokio::time::Instant::now() >= deadline {
+                return Err("Browser is still running after stop request".to_string());
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
         Ok(())
     }
 
+    /// 浏览器是否有正在运行的进程
+    pub async fn is_browser_running(&self, browser_id: &str) -> bool {
+        self.running_launches
+            .read()
+            .await
+            .values()
+            .any(|running| running.browser_id == browser_id)
+    }
+
+    /// 正在运行的启动ID
+    pub async fn running_launch_ids(&self) -> Vec<String> {
+        self.running_launches.read().await.keys().cloned().collect()
+    }
+
+    /// 获取启动历史（按启动时间倒序）
+    pub async fn list_launch_history(
+        &self,
+        browser_id: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<LaunchRecord>, String> {
+        self.database
+            .get_launch_history(browser_id, limit)
+            .await
+            .map_err(|e| format!("Failed to get launch history: {}", e))
+    }
+
+    /// 按历史记录中的参数、环境变量和工作目录重新启动浏览器
+    pub async fn relaunch(&self, launch_id: &str, acknowledged: bool) -> Result<BrowserLaunch, String> {
+        let record = self
+            .database
+            .get_launch_record(launch_id)
+            .await
+            .map_err(|e| format!("Failed to get launch record: {}", e))?
+            .ok_or("Launch record not found")?;
+
+        // 浏览器已被替换为其他版本时无法精确重现
+        let browser = self.get_browser_info(&record.browser_id).await?;
+        if browser.version != record.browser_version {
+            return Err(format!(
+                "Browser version changed since the recorded launch: {} -> {}",
+                record.browser_version, browser.version
+            ));
+        }
+
+        self.launch_browser(
+            &record.browser_id,
+            LaunchOptions {
+                args: record.args,
+                env: record.env,
+                working_dir: record.working_dir,
+                acknowledged,
+                config_ids: record.config_ids,
+                relaunch_of: Some(record.id),
+                firefox_prefs: record.firefox_prefs,
+                profile_seed: record.profile_seed,
+                policies: record.policies,
+            },
+        )
+        .await
+    }
+
+    /// 读取某次启动的输出日志，指定行数时只返回最后若干行
+    pub async fn read_launch_log(
+        &self,
+        launch_id: &str,
+        stream: LaunchLogStream,
+        lines: Option<usize>,
+    ) -> Result<String, String> {
+        uuid::Uuid::parse_str(launch_id).map_err(|_| "Invalid launch ID".to_string())?;
+
+        let log_dir = crate::utils::get_launch_logs_dir()?.join(launch_id);
+        if !log_dir.exists() {
+            return Err("Launch log not found".to_string());
+        }
+
+        crate::utils::tail_log_file(log_dir.join(stream.file_name()), lines)
+            .await
+            .map_err(|e| format!("Failed to read launch log: {}", e))
+    }
+
     pub async fn get_browser_info(&self, browser_id: &str) -> Result<BrowserInfo, String> {
         let browsers = self.list_browsers().await?;
         browsers
@@ -141,13 +1002,32 @@ impl BrowserManager {
     }
 
     /// 保存已完成下载的浏览器到数据库
-    pub async fn save_completed_browser(&self, browser_info: BrowserInfo) -> Result<(), String> {
+    pub async fn save_completed_browser(&self, mut browser_info: BrowserInfo) -> Result<(), String> {
         tracing::info!("Saving completed browser to database: {} {} {}", 
             format!("{:?}", browser_info.browser_type), 
             browser_info.version,
             browser_info.platform
         );
 
+        // 解析失败的版本别名在下载完成后才能确定构建，已有记录时沿用其ID
+        if let Some(existing) = self
+            .find_installed_build(&browser_info.browser_type, &browser_info.version, browser_info.platform)
+            .await?
+        {
+            browser_info.id = existing.id;
+            browser_info.last_launched = existing.last_launched;
+        }
+
+        // 下载任务记录的是压缩包大小，这里改为解压后实际占用的磁盘空间
+        match crate::utils::get_dir_size(&browser_info.install_path).await {
+            Ok(size) => browser_info.file_size = size,
+            Err(e) => tracing::warn!(
+                "Failed to measure install size of {}: {}",
+                browser_info.install_path.display(),
+                e
+            ),
+        }
+
         self.database
             .save_browser(&browser_info)
             .await
@@ -157,27 +1037,53 @@ impl BrowserManager {
         Ok(())
     }
 
-    /// 清理所有浏览器数据
-    pub async fn clear_all_browsers(&self) -> Result<(), String> {
-        // 获取所有浏览器
-        let browsers = self.list_browsers().await?;
-        
-        // 删除所有浏览器的文件和数据库记录
-        for browser in browsers {
-            // 删除文件系统中的浏览器目录
-            if browser.install_path.exists() && browser.install_path != std::path::PathBuf::new() {
-                if let Err(e) = tokio::fs::remove_dir_all(&browser.install_path).await {
-                    tracing::warn!("Failed to remove browser directory {:?}: {}", browser.install_path, e);
-                }
-            }
-            
-            // 从数据库删除记录
-            if let Err(e) = self.database.delete_browser(&browser.id).await {
-                tracing::warn!("Failed to delete browser {} from database: {}", browser.id, e);
+    /// 将所有受管理的浏览器移入回收站，要么全部成功，要么保持原样
+    pub async fn clear_all_browsers(&self, stop_running: bool) -> Result<ClearBrowsersReport, String> {
+        let (external, managed): (Vec<BrowserInfo>, Vec<BrowserInfo>) = self
+            .list_browsers()
+            .await?
+            .into_iter()
+            .partition(|b| b.is_external);
+
+        // 先检查运行状态，避免删除到一半才失败
+        let mut running = Vec::new();
+        for browser in &managed {
+            if self.is_browser_running(&browser.id).await {
+                running.push(browser.id.clone());
             }
         }
-        
-        tracing::info!("Cleared all browser data");
-        Ok(())
+        if !running.is_empty() && !stop_running {
+            return Err(format!(
+                "Browsers are running, stop them before clearing: {}",
+                running.join(", ")
+            ));
+        }
+        for browser_id in &running {
+            self.stop_browser(browser_id).await?;
+        }
+
+        let entries = self.move_to_trash(&managed).await?;
+
+        tracing::info!("Moved {} browser(s) to trash", entries.len());
+        Ok(ClearBrowsersReport {
+            trashed: entries.iter().map(|e| e.browser.id.clone()).collect(),
+            stopped: running,
+            skipped_external: external.into_iter().map(|b| b.id).collect(),
+            total_bytes: entries.iter().map(|e| e.size_bytes).sum(),
+        })
+    }
+}
+
+/// 在后台将进程输出写入滚动日志
+fn spawn_log_pump<R>(stream: Option<R>, path: PathBuf)
+where
+    R: tokio::io::AsyncRead + Send + Unpin + 'static,
+{
+    if let Some(stream) = stream {
+        tokio::spawn(async move {
+            if let Err(e) = crate::utils::pipe_to_rotating_log(stream, path.clone()).await {
+                tracing::warn!("Failed to write launch log {}: {}", path.display(), e);
+            }
+        });
     }
-}
\ No newline at end of file
+}
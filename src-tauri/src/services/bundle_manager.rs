@@ -0,0 +1,480 @@
+use crate::database::Database;
+use crate::models::{
+    BrowserInfo, BundleFileEntry, BundleImportReport, BundleManifest, BundledBrowser,
+    BUNDLE_FORMAT_VERSION,
+};
+use crate::services::parameter_manager::ParameterManager;
+use crate::utils::ArchiveEntry;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// 导入过程中已完成的操作，失败时按相反顺序撤销
+#[derive(Default)]
+struct ImportProgress {
+    moved_dirs: Vec<(PathBuf, PathBuf)>, // (暂存目录, 安装目录)
+    browser_ids: Vec<String>,
+    config_ids: Vec<String>,
+}
+
+/// 浏览器安装包导出/导入管理
+pub struct BundleManager {
+    database: Arc<Database>,
+    parameter_manager: Arc<ParameterManager>,
+}
+
+impl BundleManager {
+    pub fn new(database: Arc<Database>, parameter_manager: Arc<ParameterManager>) -> Self {
+        Self {
+            database,
+            parameter_manager,
+        }
+    }
+
+    /// 将已安装的浏览器及其启动配置导出为单个 tar.xz 安装包
+    pub async fn export_bundle(
+        &self,
+        browser_ids: &[String],
+        output_path: &Path,
+    ) -> Result<BundleManifest, String> {
+        if browser_ids.is_empty() {
+            return Err("No browsers selected for export".to_string());
+        }
+        Self::ensure_bundle_extension(output_path)?;
+
+        let browsers = self
+            .database
+            .get_browsers()
+            .await
+            .map_err(|e| format!("Failed to get browsers: {}", e))?;
+
+        let mut bundled = Vec::new();
+        for browser_id in browser_ids {
+            let browser = browsers
+                .iter()
+                .find(|b| &b.id == browser_id)
+                .ok_or_else(|| format!("Browser not found: {}", browser_id))?;
+
+            if browser.is_external {
+                return Err(format!(
+                    "External browser {} cannot be exported",
+                    browser.id
+                ));
+            }
+            if !browser.install_path.is_dir() {
+                return Err(format!(
+                    "Install directory missing: {}",
+                    browser.install_path.to_string_lossy()
+                ));
+            }
+
+            bundled.push(Self::describe_browser(browser.clone()).await?);
+        }
+
+        let platform = bundled[0].info.platform;
+        if bundled.iter().any(|b| b.info.platform != platform) {
+            return Err("All browsers in a bundle must target the same platform".to_string());
+        }
+
+        let launch_configs = self
+            .parameter_manager
+            .get_all_configs()
+            .await?
+            .into_iter()
+            .filter(|config| {
+                config
+                    .browser_id
+                    .as_ref()
+                    .map(|id| browser_ids.contains(id))
+                    .unwrap_or(false)
+            })
+            .collect();
+
+        let manifest = BundleManifest {
+            format_version: BUNDLE_FORMAT_VERSION,
+            created_at: chrono::Utc::now(),
+            platform,
+            browsers: bundled,
+            launch_configs,
+        };
+
+        let manifest_json = serde_json::to_vec_pretty(&manifest)
+            .map_err(|e| format!("Failed to serialize bundle manifest: {}", e))?;
+
+        let mut entries = vec![ArchiveEntry::Data {
+            name: MANIFEST_FILE.to_string(),
+            content: manifest_json,
+        }];
+        entries.extend(manifest.browsers.iter().map(|b| ArchiveEntry::Dir {
+            name: b.archive_dir(),
+            source: b.info.install_path.clone(),
+        }));
+
+        crate::utils::create_tar_xz(output_path, entries).await?;
+
+        info!(
+            "Exported {} browsers and {} launch configs to {:?}",
+            manifest.browsers.len(),
+            manifest.launch_configs.len(),
+            output_path
+        );
+        Ok(manifest)
+    }
+
+    /// 导入安装包：校验清单和校验和，恢复文件并重新注册到数据库
+    pub async fn import_bundle(&self, bundle_path: &Path) -> Result<BundleImportReport, String> {
+        if !bundle_path.is_file() {
+            return Err(format!("Bundle not found: {}", bundle_path.to_string_lossy()));
+        }
+        Self::ensure_bundle_extension(bundle_path)?;
+
+        let browsers_dir = crate::utils::get_browsers_dir()?;
+        let staging_dir = browsers_dir.join(format!(".bundle-{}", uuid::Uuid::new_v4()));
+        crate::utils::ensure_dir_exists(&staging_dir).await?;
+
+        let result = self
+            .import_from_staging(bundle_path, &staging_dir, &browsers_dir)
+            .await;
+
+        if let Err(e) = tokio::fs::remove_dir_all(&staging_dir).await {
+            warn!("Failed to remove bundle staging directory {:?}: {}", staging_dir, e);
+        }
+
+        result
+    }
+
+    async fn import_from_staging(
+        &self,
+        bundle_path: &Path,
+        staging_dir: &Path,
+        browsers_dir: &Path,
+    ) -> Result<BundleImportReport, String> {
+        crate::utils::extract_archive(bundle_path, staging_dir).await?;
+
+        let manifest_content = tokio::fs::read(staging_dir.join(MANIFEST_FILE))
+            .await
+            .map_err(|e| format!("Bundle manifest missing: {}", e))?;
+        let manifest: BundleManifest = serde_json::from_slice(&manifest_content)
+            .map_err(|e| format!("Invalid bundle manifest: {}", e))?;
+
+        Self::validate_manifest(&manifest)?;
+        Self::verify_staging(&manifest, staging_dir).await?;
+
+        let mut progress = ImportProgress::default();
+        match self
+            .install_from_staging(&manifest, staging_dir, browsers_dir, &mut progress)
+            .await
+        {
+            Ok(report) => {
+                info!(
+                    "Imported bundle {:?}: {} browsers, {} launch configs, {} skipped",
+                    bundle_path,
+                    report.imported_browsers.len(),
+                    report.imported_configs.len(),
+                    report.skipped.len()
+                );
+                Ok(report)
+            }
+            Err(e) => {
+                self.rollback_import(progress).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// 将校验通过的浏览器移入安装目录并注册，已完成的操作记录在 progress 中
+    async fn install_from_staging(
+        &self,
+        manifest: &BundleManifest,
+        staging_dir: &Path,
+        browsers_dir: &Path,
+        progress: &mut ImportProgress,
+    ) -> Result<BundleImportReport, String> {
+        let existing = self
+            .database
+            .get_browsers()
+            .await
+            .map_err(|e| format!("Failed to get browsers: {}", e))?;
+
+        let mut report = BundleImportReport {
+            imported_browsers: Vec::new(),
+            imported_configs: Vec::new(),
+            skipped: Vec::new(),
+        };
+        // 安装包中的浏览器ID -> 新注册的浏览器ID
+        let mut id_map: HashMap<String, String> = HashMap::new();
+
+        for bundled in &manifest.browsers {
+            let info = &bundled.info;
+            let browser_key = crate::utils::get_browser_key(&info.browser_type);
+
+            let already_installed = existing.iter().any(|b| {
+                crate::utils::get_browser_key(&b.browser_type) == browser_key
+                    && b.version == info.version
+                    && b.platform == info.platform
+            });
+            let install_path = browsers_dir
+                .join(browser_key)
+                .join(format!("{}-{}", info.platform, info.version));
+
+            if already_installed || install_path.exists() {
+                report.skipped.push(format!(
+                    "{:?} {} ({}) is already installed",
+                    info.browser_type, info.version, info.platform
+                ));
+                continue;
+            }
+
+            if let Some(parent) = install_path.parent() {
+                crate::utils::ensure_dir_exists(parent).await?;
+            }
+            let staged_path = staging_dir.join(bundled.archive_dir());
+            tokio::fs::rename(&staged_path, &install_path)
+                .await
+                .map_err(|e| format!("Failed to move browser into place: {}", e))?;
+            progress.moved_dirs.push((staged_path, install_path.clone()));
+
+            let browser_info = BrowserInfo {
+                id: uuid::Uuid::new_v4().to_string(),
+                browser_type: info.browser_type.clone(),
+                version: info.version.clone(),
+                platform: info.platform,
+                executable_path: install_path.join(&bundled.executable_relative_path),
+                install_path,
+                download_date: chrono::Utc::now(),
+                file_size: bundled.total_size(),
+                is_running: false,
+                is_external: false,
+                last_launched: None,
+            };
+
+            self.database
+                .save_browser(&browser_info)
+                .await
+                .map_err(|e| format!("Failed to save browser to database: {}", e))?;
+            progress.browser_ids.push(browser_info.id.clone());
+
+            id_map.insert(info.id.clone(), browser_info.id.clone());
+            report.imported_browsers.push(browser_info);
+        }
+
+        // 只导入关联到本次新注册浏览器的配置，并重新生成ID
+        for config in &manifest.launch_configs {
+            let Some(new_browser_id) = config.browser_id.as_ref().and_then(|id| id_map.get(id))
+            else {
+                report
+                    .skipped
+                    .push(format!("Launch config '{}' has no imported browser", config.name));
+                continue;
+            };
+
+            let mut imported = config.clone();
+            imported.id = uuid::Uuid::new_v4().to_string();
+            imported.browser_id = Some(new_browser_id.clone());
+            imported.is_default = false;
+            imported.created_at = chrono::Utc::now();
+
+            self.parameter_manager.save_config(imported.clone()).await?;
+            progress.config_ids.push(imported.id.clone());
+            report.imported_configs.push(imported);
+        }
+
+        Ok(report)
+    }
+
+    /// 撤销导入失败前已完成的操作：删除新注册的配置和浏览器，并将文件移回暂存目录
+    async fn rollback_import(&self, progress: ImportProgress) {
+        for config_id in progress.config_ids.iter().rev() {
+            if let Err(e) = self.parameter_manager.delete_config(config_id).await {
+                warn!("Failed to roll back imported launch config {}: {}", config_id, e);
+            }
+        }
+        for browser_id in progress.browser_ids.iter().rev() {
+            if let Err(e) = self.database.delete_browser(browser_id).await {
+                warn!("Failed to roll back imported browser {}: {}", browser_id, e);
+            }
+        }
+        for (staged_path, install_path) in progress.moved_dirs.iter().rev() {
+            if let Err(e) = tokio::fs::rename(install_path, staged_path).await {
+                warn!("Failed to move {:?} back to staging: {}", install_path, e);
+                if let Err(e) = tokio::fs::remove_dir_all(install_path).await {
+                    warn!("Failed to remove partially imported browser {:?}: {}", install_path, e);
+                }
+            }
+        }
+    }
+
+    fn ensure_bundle_extension(path: &Path) -> Result<(), String> {
+        match crate::utils::ArchiveFormat::from_path(path) {
+            Some(crate::utils::ArchiveFormat::TarXz) => Ok(()),
+            _ => Err("Bundle file must use the .tar.xz extension".to_string()),
+        }
+    }
+
+    fn validate_manifest(manifest: &BundleManifest) -> Result<(), String> {
+        if manifest.format_version != BUNDLE_FORMAT_VERSION {
+            return Err(format!(
+                "Unsupported bundle format version: {} (expected {})",
+                manifest.format_version, BUNDLE_FORMAT_VERSION
+            ));
+        }
+
+        if !manifest.platform.is_runnable()
+            || manifest.browsers.iter().any(|b| b.info.platform != manifest.platform)
+        {
+            return Err(format!(
+                "Bundle platform {} cannot run on this machine",
+                manifest.platform
+            ));
+        }
+
+        for bundled in &manifest.browsers {
+            // 防止通过清单路径写出安装目录
+            let escapes = |p: &str| {
+                Path::new(p)
+                    .components()
+                    .any(|c| !matches!(c, std::path::Component::Normal(_)))
+            };
+            if escapes(&bundled.executable_relative_path)
+                || bundled.files.iter().any(|f| escapes(&f.path))
+            {
+                return Err(format!("Invalid path in bundle manifest for {}", bundled.info.id));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 校验暂存目录：只允许清单中列出的文件和指向所在浏览器目录内部的符号链接，并核对校验和
+    async fn verify_staging(manifest: &BundleManifest, staging_dir: &Path) -> Result<(), String> {
+        let mut allowed: HashSet<String> = HashSet::from([MANIFEST_FILE.to_string()]);
+        let mut checks: Vec<(PathBuf, BundleFileEntry)> = Vec::new();
+        let mut browser_dirs: Vec<String> = Vec::new();
+        for bundled in &manifest.browsers {
+            let dir = bundled.archive_dir();
+            for file in &bundled.files {
+                allowed.insert(format!("{}/{}", dir, file.path));
+                checks.push((staging_dir.join(&dir).join(&file.path), file.clone()));
+            }
+            browser_dirs.push(dir);
+        }
+        let staging_dir = staging_dir.to_path_buf();
+
+        tokio::task::spawn_blocking(move || {
+            Self::check_staged_entries(&staging_dir, &allowed, &browser_dirs)?;
+
+            for (path, entry) in checks {
+                // 清单中的文件必须是普通文件，不能经由符号链接读取其他内容
+                let is_file = std::fs::symlink_metadata(&path)
+                    .map(|m| m.file_type().is_file())
+                    .unwrap_or(false);
+                if !is_file {
+                    return Err(format!("Bundle file {} is missing", entry.path));
+                }
+                let checksum = crate::utils::calculate_checksum_blocking(&path)
+                    .map_err(|e| format!("Bundle file {} unreadable: {}", entry.path, e))?;
+                if checksum != entry.sha256 {
+                    return Err(format!("Checksum mismatch for bundle file {}", entry.path));
+                }
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| format!("Checksum verification task failed: {}", e))?
+    }
+
+    /// 遍历暂存目录，拒绝清单以外的文件、特殊文件和指向浏览器目录以外的符号链接（阻塞调用）
+    fn check_staged_entries(
+        staging_dir: &Path,
+        allowed: &HashSet<String>,
+        browser_dirs: &[String],
+    ) -> Result<(), String> {
+        let mut pending = vec![staging_dir.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            let entries = std::fs::read_dir(&dir)
+                .map_err(|e| format!("Failed to read bundle contents: {}", e))?;
+            for entry in entries {
+                let entry = entry.map_err(|e| format!("Failed to read bundle contents: {}", e))?;
+                let path = entry.path();
+                let relative = path
+                    .strip_prefix(staging_dir)
+                    .map_err(|_| format!("Unexpected bundle entry: {}", path.display()))?
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                let file_type = entry
+                    .file_type()
+                    .map_err(|e| format!("Failed to read bundle entry {}: {}", relative, e))?;
+
+                if file_type.is_dir() {
+                    pending.push(path);
+                } else if file_type.is_symlink() {
+                    // 绝对路径在移入安装目录后会失效；相对路径解析后必须仍在所在浏览器目录内
+                    let target = std::fs::read_link(&path)
+                        .map_err(|e| format!("Failed to read symlink {}: {}", relative, e))?;
+                    let resolved = std::fs::canonicalize(&path).ok();
+                    let inside = !target.has_root()
+                        && browser_dirs.iter().any(|dir| {
+                            let root = std::fs::canonicalize(staging_dir.join(dir)).ok();
+                            relative.starts_with(&format!("{}/", dir))
+                                && matches!((&resolved, &root), (Some(r), Some(root)) if r.starts_with(root))
+                        });
+                    if !inside {
+                        return Err(format!(
+                            "Bundle symlink {} points outside its browser directory",
+                            relative
+                        ));
+                    }
+                } else if !file_type.is_file() {
+                    return Err(format!("Unsupported file type in bundle: {}", relative));
+                } else if !allowed.contains(&relative) {
+                    return Err(format!("Bundle file {} is not listed in the manifest", relative));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 收集浏览器安装目录中的文件清单和校验和
+    async fn describe_browser(info: BrowserInfo) -> Result<BundledBrowser, String> {
+        let executable_relative_path = info
+            .executable_path
+            .strip_prefix(&info.install_path)
+            .map_err(|_| format!("Executable of {} is outside its install directory", info.id))?
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let install_path = info.install_path.clone();
+        let files = tokio::task::spawn_blocking(move || -> Result<Vec<BundleFileEntry>, String> {
+            let relative_paths = crate::utils::list_files_recursive(&install_path)
+                .map_err(|e| format!("Failed to list browser files: {}", e))?;
+
+            relative_paths
+                .into_iter()
+                .map(|relative| {
+                    let full_path = install_path.join(&relative);
+                    let size = std::fs::metadata(&full_path)
+                        .map_err(|e| format!("Failed to read file metadata: {}", e))?
+                        .len();
+                    let sha256 = crate::utils::calculate_checksum_blocking(&full_path)
+                        .map_err(|e| format!("Failed to checksum file: {}", e))?;
+                    Ok(BundleFileEntry {
+                        path: relative.to_string_lossy().replace('\\', "/"),
+                        size,
+                        sha256,
+                    })
+                })
+                .collect()
+        })
+        .await
+        .map_err(|e| format!("Checksum task failed: {}", e))??;
+
+        Ok(BundledBrowser {
+            info,
+            executable_relative_path,
+            files,
+        })
+    }
+}
@@ -0,0 +1,366 @@
+use crate::models::{
+    BrowserFamily, CaptureArtifact, CaptureFormat, CaptureRequest, CaptureResult, LaunchOptions, VisualDiffOptions,
+    VisualDiffResult, VisualDiffSource,
+};
+use crate::services::{BrowserManager, ParameterManager};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 默认的单次捕获超时时间
+const DEFAULT_CAPTURE_TIMEOUT_SECS: u64 = 60;
+/// 捕获失败时附带的 stderr 行数
+const CAPTURE_ERROR_LINES: usize = 5;
+
+pub struct CaptureManager {
+    browser_manager: Arc<BrowserManager>,
+    parameter_manager: Arc<ParameterManager>,
+}
+
+impl CaptureManager {
+    pub fn new(browser_manager: Arc<BrowserManager>, parameter_manager: Arc<ParameterManager>) -> Self {
+        Self {
+            browser_manager,
+            parameter_manager,
+        }
+    }
+
+    /// 用多个浏览器版本以无头模式打开页面，保存截图或 PDF 及元数据
+    pub async fn capture_page(&self, request: CaptureRequest) -> Result<CaptureResult, String> {
+        if request.browser_ids.is_empty() {
+            return Err("No browsers specified for capture".to_string());
+        }
+        if request.formats.is_empty() {
+            return Err("No capture formats specified".to_string());
+        }
+
+        let url = normalize_capture_url(&request.url)?;
+        let capture_id = uuid::Uuid::new_v4().to_string();
+        let artifacts_dir = crate::utils::get_artifacts_dir()?.join(&capture_id);
+        crate::utils::ensure_dir_exists(&artifacts_dir).await?;
+
+        let mut artifacts = Vec::new();
+        for browser_id in &request.browser_ids {
+            for format in &request.formats {
+                artifacts.push(
+                    self.capture_with_browser(&request, &url, browser_id, *format, &artifacts_dir)
+                        .await,
+                );
+            }
+        }
+
+        let result = CaptureResult {
+            capture_id,
+            url,
+            captured_at: chrono::Utc::now(),
+            artifacts_dir: artifacts_dir.to_string_lossy().to_string(),
+            viewport_width: request.viewport_width,
+            viewport_height: request.viewport_height,
+            artifacts,
+        };
+
+        let metadata = serde_json::to_string_pretty(&result)
+            .map_err(|e| format!("Failed to serialize capture metadata: {}", e))?;
+        tokio::fs::write(artifacts_dir.join("capture.json"), metadata)
+            .await
+            .map_err(|e| format!("Failed to write capture metadata: {}", e))?;
+
+        Ok(result)
+    }
+
+    /// 对比两张截图，生成差异图像并按阈值判断是否通过
+    pub async fn compare_screenshots(
+        &self,
+        baseline: VisualDiffSource,
+        candidate: VisualDiffSource,
+        options: VisualDiffOptions,
+    ) -> Result<VisualDiffResult, String> {
+        let baseline_path = resolve_diff_source(&baseline).await?;
+        let candidate_path = resolve_diff_source(&candidate).await?;
+
+        let diffs_dir = crate::utils::get_artifacts_dir()?.join("diffs");
+        crate::utils::ensure_dir_exists(&diffs_dir).await?;
+        let diff_id = uuid::Uuid::new_v4().to_string();
+        let diff_image_path = diffs_dir.join(format!("{}.png", diff_id));
+
+        let (a, b, tolerance, output) = (
+            baseline_path.clone(),
+            candidate_path.clone(),
+            options.pixel_tolerance,
+            diff_image_path.clone(),
+        );
+        let diff = tokio::task::spawn_blocking(move || -> Result<crate::utils::ImageDiff, String> {
+            let diff = crate::utils::diff_images(
+                &crate::utils::load_png(&a)?,
+                &crate::utils::load_png(&b)?,
+                tolerance,
+            );
+            crate::utils::save_png(&output, &diff.diff_image)?;
+            Ok(diff)
+        })
+        .await
+        .map_err(|e| format!("Image comparison task failed: {}", e))??;
+
+        let diff_ratio = if diff.total_pixels == 0 {
+            0.0
+        } else {
+            diff.different_pixels as f64 / diff.total_pixels as f64
+        };
+        let passed = !diff.size_mismatch
+            && diff_ratio <= options.max_diff_ratio
+            && !matches!(
+                options.max_perceptual_difference,
+                Some(max) if diff.perceptual_difference > max
+            );
+
+        let result = VisualDiffResult {
+            diff_id,
+            baseline_path: baseline_path.to_string_lossy().to_string(),
+            candidate_path: candidate_path.to_string_lossy().to_string(),
+            width: diff.width,
+            height: diff.height,
+            size_mismatch: diff.size_mismatch,
+            different_pixels: diff.different_pixels,
+            total_pixels: diff.total_pixels,
+            diff_ratio,
+            perceptual_difference: diff.perceptual_difference,
+            diff_image_path: diff_image_path.to_string_lossy().to_string(),
+            options,
+            passed,
+            compared_at: chrono::Utc::now(),
+        };
+
+        let metadata = serde_json::to_string_pretty(&result)
+            .map_err(|e| format!("Failed to serialize diff metadata: {}", e))?;
+        tokio::fs::write(diffs_dir.join(format!("{}.json", result.diff_id)), metadata)
+            .await
+            .map_err(|e| format!("Failed to write diff metadata: {}", e))?;
+
+        Ok(result)
+    }
+
+    /// 将截图保存为基准，同名基准会被覆盖
+    pub async fn save_baseline(&self, name: &str, source: VisualDiffSource) -> Result<String, String> {
+        let target = baseline_path(name)?;
+        let source_path = resolve_diff_source(&source).await?;
+
+        crate::utils::ensure_dir_exists(target.parent().ok_or("Invalid baseline path")?).await?;
+        tokio::fs::copy(&source_path, &target)
+            .await
+            .map_err(|e| format!("Failed to save baseline: {}", e))?;
+
+        Ok(target.to_string_lossy().to_string())
+    }
+
+    async fn capture_with_browser(
+        &self,
+        request: &CaptureRequest,
+        url: &str,
+        browser_id: &str,
+        format: CaptureFormat,
+        artifacts_dir: &Path,
+    ) -> CaptureArtifact {
+        let started = Instant::now();
+        let mut artifact = CaptureArtifact {
+            browser_id: browser_id.to_string(),
+            browser_version: String::new(),
+            format,
+            path: None,
+            file_size: 0,
+            args: Vec::new(),
+            exit_code: None,
+            duration_ms: 0,
+            error: None,
+        };
+
+        // 每次捕获使用临时的用户数据目录，结束后删除
+        let profile_dir = std::env::temp_dir().join(format!("chrome-tester-capture-{}", uuid::Uuid::new_v4()));
+        let result = self
+            .run_capture(request, url, format, artifacts_dir, &profile_dir, &mut artifact)
+            .await;
+        if profile_dir.exists() {
+            if let Err(e) = tokio::fs::remove_dir_all(&profile_dir).await {
+                tracing::warn!("Failed to remove capture profile {}: {}", profile_dir.display(), e);
+            }
+        }
+
+        artifact.duration_ms = started.elapsed().as_millis() as u64;
+        if let Err(e) = result {
+            tracing::warn!("Capture with browser {} failed: {}", browser_id, e);
+            artifact.error = Some(e);
+        }
+        artifact
+    }
+
+    async fn run_capture(
+        &self,
+        request: &CaptureRequest,
+        url: &str,
+        format: CaptureFormat,
+        artifacts_dir: &Path,
+        profile_dir: &Path,
+        artifact: &mut CaptureArtifact,
+    ) -> Result<(), String> {
+        let browser_id = artifact.browser_id.clone();
+        let browser = self.browser_manager.get_browser_info(&browser_id).await?;
+        artifact.browser_version = browser.version.clone();
+        // 截图和导出 PDF 依赖 Chromium 的无头模式开关
+        if browser.browser_type.family() != BrowserFamily::Chromium {
+            return Err(format!(
+                "Page capture is not supported for {} browsers",
+                browser.browser_type
+            ));
+        }
+
+        let output_path = artifacts_dir.join(format!(
+            "{}-{}.{}",
+            sanitize_file_component(&browser.version),
+            sanitize_file_component(&browser.id),
+            format.extension()
+        ));
+        let output = output_path.to_string_lossy().to_string();
+
+        // 不依赖 GPU 的无头模式参数，适用于无显示环境；参数层被禁用时也必须生效
+        let mut required_args = vec![
+            "--headless".to_string(),
+            "--disable-gpu".to_string(),
+            "--disable-dev-shm-usage".to_string(),
+            "--hide-scrollbars".to_string(),
+            "--no-default-browser-check".to_string(),
+            format!("--user-data-dir={}", profile_dir.to_string_lossy()),
+            format!("--window-size={},{}", request.viewport_width, request.viewport_height),
+        ];
+        if request.no_sandbox {
+            required_args.push("--no-sandbox".to_string());
+        }
+        if let Some(wait_ms) = request.wait_ms {
+            required_args.push(format!("--virtual-time-budget={}", wait_ms));
+        }
+        required_args.extend(format.output_args(&output));
+        required_args.push(url.to_string());
+
+        let resolved = self
+            .parameter_manager
+            .resolve_launch_args_with(
+                &browser_id,
+                request.config_id.clone().map(|id| vec![id]),
+                None,
+                &required_args,
+            )
+            .await?;
+        artifact.args = resolved.args.clone();
+
+        let timeout = Duration::from_secs(request.timeout_secs.unwrap_or(DEFAULT_CAPTURE_TIMEOUT_SECS));
+        let options = LaunchOptions {
+            args: resolved.args,
+            env: resolved.env,
+            working_dir: resolved.working_dir,
+            acknowledged: request.acknowledged,
+            config_ids: resolved.config_ids,
+            relaunch_of: None,
+            firefox_prefs: resolved.firefox_prefs,
+            profile_seed: resolved.profile_seed,
+            policies: resolved.policies,
+        };
+        let process = self
+            .browser_manager
+            .run_headless(&browser_id, options, timeout)
+            .await?;
+        artifact.exit_code = process.status.code();
+
+        let file_size = tokio::fs::metadata(&output_path)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0);
+        if file_size == 0 {
+            let stderr = String::from_utf8_lossy(&process.stderr);
+            let lines: Vec<&str> = stderr.lines().collect();
+            return Err(format!(
+                "Browser produced no output ({}): {}",
+                process.status,
+                lines[lines.len().saturating_sub(CAPTURE_ERROR_LINES)..].join("\n")
+            ));
+        }
+
+        artifact.path = Some(output);
+        artifact.file_size = file_size;
+        Ok(())
+    }
+}
+
+/// 找到对比来源对应的 PNG 文件
+async fn resolve_diff_source(source: &VisualDiffSource) -> Result<PathBuf, String> {
+    let path = match source {
+        VisualDiffSource::Capture {
+            capture_id,
+            browser_id,
+        } => {
+            uuid::Uuid::parse_str(capture_id).map_err(|_| "Invalid capture ID".to_string())?;
+            let metadata_path = crate::utils::get_artifacts_dir()?
+                .join(capture_id)
+                .join("capture.json");
+            let content = tokio::fs::read_to_string(&metadata_path)
+                .await
+                .map_err(|_| format!("Capture not found: {}", capture_id))?;
+            let capture: CaptureResult = serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse capture metadata: {}", e))?;
+
+            capture
+                .artifacts
+                .into_iter()
+                .find(|a| &a.browser_id == browser_id && a.format == CaptureFormat::Screenshot)
+                .and_then(|a| a.path)
+                .map(PathBuf::from)
+                .ok_or_else(|| {
+                    format!("No screenshot from browser {} in capture {}", browser_id, capture_id)
+                })?
+        }
+        VisualDiffSource::Baseline { name } => baseline_path(name)?,
+        VisualDiffSource::File { path } => PathBuf::from(path),
+    };
+
+    if !path.is_file() {
+        return Err(format!("Image not found: {}", path.display()));
+    }
+    Ok(path)
+}
+
+fn baseline_path(name: &str) -> Result<PathBuf, String> {
+    if name.is_empty() || sanitize_file_component(name) != name || name.starts_with('.') {
+        return Err(format!("Invalid baseline name: {}", name));
+    }
+    Ok(crate::utils::get_artifacts_dir()?
+        .join("baselines")
+        .join(format!("{}.png", name)))
+}
+
+/// 本地文件路径转换为 file:// URL，其他地址保持不变
+fn normalize_capture_url(url: &str) -> Result<String, String> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err("URL is required".to_string());
+    }
+    if url.contains("://") || url.starts_with("about:") || url.starts_with("data:") {
+        return Ok(url.to_string());
+    }
+
+    let path = PathBuf::from(url);
+    if !path.exists() {
+        return Err(format!("Page not found: {}", url));
+    }
+    let path = std::fs::canonicalize(&path).map_err(|e| format!("Failed to resolve page path: {}", e))?;
+    let path = path.to_string_lossy().replace('\\', "/");
+    Ok(if path.starts_with('/') {
+        format!("file://{}", path)
+    } else {
+        format!("file:///{}", path)
+    })
+}
+
+/// 文件名中只保留字母、数字、点、下划线和连字符
+fn sanitize_file_component(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') { c } else { '_' })
+        .collect()
+}
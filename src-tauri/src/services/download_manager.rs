@@ -1,4 +1,4 @@
-use crate::models::{BrowserInfo, DownloadTask, DownloadStatus, DownloadProgress, DownloadError};
+use crate::models::{BrowserInfo, BrowserType, DownloadTask, Platform, DownloadStatus, DownloadProgress, DownloadError};
 use crate::services::nodejs_runtime::NodejsRuntime;
 use crate::services::retry_manager::RetryManager;
 use std::collections::HashMap;
@@ -173,18 +173,13 @@ impl DownloadManager {
         }
 
         // 使用 Node.js 运行时下载浏览器
-        let browser_type_str = match browser_info.browser_type {
-            crate::models::BrowserType::Chrome => "chrome",
-            crate::models::BrowserType::Chromium => "chromium", 
-            crate::models::BrowserType::Firefox => "firefox",
-            crate::models::BrowserType::ChromeDriver => "chromedriver",
-        };
+        let browser_type_str = crate::utils::get_browser_key(&browser_info.browser_type);
 
         let download_result = nodejs_runtime
             .download_browser(
                 browser_type_str,
-                &browser_info.version,
-                &browser_info.platform,
+                &browser_info.browser_type.build_id(&browser_info.version),
+                browser_info.platform.download_key(),
                 Box::new({
                     let download_tasks = download_tasks.clone();
                     let task_id = task_id.clone();
@@ -227,7 +222,8 @@ impl DownloadManager {
         match download_result {
             Ok((install_path, executable_path, actual_version)) => {
                 let install_path: std::path::PathBuf = install_path;
-                let actual_version: String = actual_version;
+                // 下载脚本返回 build ID，Firefox 需要去掉渠道前缀
+                let actual_version = browser_info.browser_type.version_from_build_id(&actual_version);
                 // 下载完成，更新任务状态和获取完整的浏览器信息
                 let completed_browser_info = {
                     let mut tasks = download_tasks.write().await;
@@ -241,7 +237,7 @@ impl DownloadManager {
                         let exec_path = if let Some(exec_path) = &executable_path {
                             exec_path.clone()
                         } else {
-                            Self::find_executable(&install_path, &browser_info.browser_type)
+                            Self::find_executable(&install_path, &browser_info.browser_type, browser_info.platform)
                         };
                         task.browser_info.executable_path = exec_path;
                         
@@ -258,7 +254,7 @@ impl DownloadManager {
                         let exec_path = if let Some(exec_path) = &executable_path {
                             exec_path.clone()
                         } else {
-                            Self::find_executable(&install_path, &browser_info.browser_type)
+                            Self::find_executable(&install_path, &browser_info.browser_type, browser_info.platform)
                         };
                         info.executable_path = exec_path;
                         info
@@ -314,27 +310,45 @@ impl DownloadManager {
         }
     }
 
-    fn find_executable(install_path: &std::path::Path, browser_type: &crate::models::BrowserType) -> std::path::PathBuf {
-        // 根据浏览器类型和平台生成可能的可执行文件路径
+    /// 根据浏览器类型在安装目录中查找可执行文件
+    pub fn find_executable(
+        install_path: &std::path::Path,
+        browser_type: &crate::models::BrowserType,
+        platform: Platform,
+    ) -> std::path::PathBuf {
+        // 根据浏览器类型和构建的目标平台（不一定是当前系统）生成可能的可执行文件路径
         let possible_paths = match browser_type {
             crate::models::BrowserType::Chrome => {
-                if cfg!(target_os = "windows") {
-                    vec!["chrome.exe", "Application/chrome.exe"]
-                } else if cfg!(target_os = "macos") {
+                if platform.is_windows() {
+                    vec![
+                        "chrome.exe",
+                        "Application/chrome.exe",
+                        "chrome-win64/chrome.exe",
+                        "chrome-win32/chrome.exe",
+                    ]
+                } else if platform.is_mac() {
                     vec![
                         "Google Chrome.app/Contents/MacOS/Google Chrome",
                         "chrome-mac/Google Chrome.app/Contents/MacOS/Google Chrome",
                         "chrome-mac-arm64/Google Chrome.app/Contents/MacOS/Google Chrome",
                         "chrome-mac-x64/Google Chrome.app/Contents/MacOS/Google Chrome",
+                        "chrome-mac-arm64/Google Chrome for Testing.app/Contents/MacOS/Google Chrome for Testing",
+                        "chrome-mac-x64/Google Chrome for Testing.app/Contents/MacOS/Google Chrome for Testing",
                     ]
                 } else {
-                    vec!["chrome", "google-chrome", "chrome-linux/chrome"]
+                    vec![
+                        "chrome",
+                        "google-chrome",
+                        "chrome-linux/chrome",
+                        "chrome-linux64/chrome",
+                        "chrome-linux-arm64/chrome",
+                    ]
                 }
             }
             crate::models::BrowserType::Chromium => {
-                if cfg!(target_os = "windows") {
-                    vec!["chrome.exe", "Application/chrome.exe"]
-                } else if cfg!(target_os = "macos") {
+                if platform.is_windows() {
+                    vec!["chrome.exe", "Application/chrome.exe", "chrome-win/chrome.exe"]
+                } else if platform.is_mac() {
                     vec![
                         "Chromium.app/Contents/MacOS/Chromium",
                         "chrome-mac/Chromium.app/Contents/MacOS/Chromium",
@@ -343,20 +357,62 @@ impl DownloadManager {
                     vec!["chrome", "chromium", "chrome-linux/chrome"]
                 }
             }
-            crate::models::BrowserType::Firefox => {
-                if cfg!(target_os = "windows") {
-                    vec!["firefox.exe", "firefox/firefox.exe"]
-                } else if cfg!(target_os = "macos") {
-                    vec!["Firefox.app/Contents/MacOS/firefox"]
+            // Nightly 解压到 firefox 目录，其他渠道在 Windows 上由安装程序解压到 core 目录
+            crate::models::BrowserType::Firefox
+            | crate::models::BrowserType::FirefoxNightly
+            | crate::models::BrowserType::FirefoxDevEdition
+            | crate::models::BrowserType::FirefoxEsr => {
+                if platform.is_windows() {
+                    vec!["firefox.exe", "firefox/firefox.exe", "core/firefox.exe"]
+                } else if platform.is_mac() {
+                    vec![
+                        "Firefox.app/Contents/MacOS/firefox",
+                        "Firefox Nightly.app/Contents/MacOS/firefox",
+                        "Firefox Developer Edition.app/Contents/MacOS/firefox",
+                    ]
                 } else {
-                    vec!["firefox"]
+                    vec!["firefox", "firefox/firefox"]
+                }
+            }
+            crate::models::BrowserType::ChromeHeadlessShell => {
+                if platform.is_windows() {
+                    vec![
+                        "chrome-headless-shell.exe",
+                        "chrome-headless-shell-win64/chrome-headless-shell.exe",
+                        "chrome-headless-shell-win32/chrome-headless-shell.exe",
+                    ]
+                } else if platform.is_mac() {
+                    vec![
+                        "chrome-headless-shell",
+                        "chrome-headless-shell-mac-arm64/chrome-headless-shell",
+                        "chrome-headless-shell-mac-x64/chrome-headless-shell",
+                    ]
+                } else {
+                    vec![
+                        "chrome-headless-shell",
+                        "chrome-headless-shell-linux64/chrome-headless-shell",
+                    ]
                 }
             }
             crate::models::BrowserType::ChromeDriver => {
-                if cfg!(target_os = "windows") {
-                    vec!["chromedriver.exe"]
+                if platform.is_windows() {
+                    vec![
+                        "chromedriver.exe",
+                        "chromedriver-win64/chromedriver.exe",
+                        "chromedriver-win32/chromedriver.exe",
+                    ]
+                } else if platform.is_mac() {
+                    vec![
+                        "chromedriver",
+                        "chromedriver-mac-arm64/chromedriver",
+                        "chromedriver-mac-x64/chromedriver",
+                    ]
                 } else {
-                    vec!["chromedriver"]
+                    vec![
+                        "chromedriver",
+                        "chromedriver-linux64/chromedriver",
+                        "chromedriver-linux-arm64/chromedriver",
+                    ]
                 }
             }
         };
@@ -428,6 +484,42 @@ impl DownloadManager {
         tasks.get(task_id).cloned()
     }
 
+    /// 查找同一构建尚未结束的下载任务
+    pub async fn find_active_task(
+        &self,
+        browser_type: &BrowserType,
+        versions: &[&str],
+        platform: Platform,
+    ) -> Option<String> {
+        let tasks = self.download_tasks.read().await;
+        tasks
+            .values()
+            .find(|task| {
+                task.status.is_active()
+                    && task.browser_info.browser_type == *browser_type
+                    && task.browser_info.platform == platform
+                    && versions.contains(&task.browser_info.version.as_str())
+            })
+            .map(|task| task.id.clone())
+    }
+
+    pub async fn resolve_build_id(
+        &self,
+        browser_type: &BrowserType,
+        version: &str,
+        platform: Platform,
+    ) -> Result<String, String> {
+        let build_id = self
+            .nodejs_runtime
+            .resolve_build_id(
+                crate::utils::get_browser_key(browser_type),
+                &browser_type.build_id(version),
+                platform.download_key(),
+            )
+            .await?;
+        Ok(browser_type.version_from_build_id(&build_id))
+    }
+
     pub async fn list_download_tasks(&self) -> Vec<DownloadTask> {
         let tasks = self.download_tasks.read().await;
         tasks.values().cloned().collect()
@@ -0,0 +1,384 @@
+use crate::models::{ChromeSwitch, LaunchParameter, SwitchCatalog, SwitchValueType};
+use crate::utils::{find_placeholders, is_firefox_arg};
+use serde::{Deserialize, Serialize};
+
+/// 校验问题的严重程度
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, PartialOrd)]
+pub enum ValidationSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// 校验问题类型
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum FlagIssueKind {
+    UnknownSwitch,   // 目录中不存在的开关
+    Mistyped,        // 拼写/格式错误（如下划线、单横线）
+    MisplacedV8Flag, // V8 参数应通过 --js-flags 传递
+    Obsolete,        // 已移除
+    Deprecated,      // 已废弃
+    NotYetSupported, // 当前版本尚不支持
+    MissingValue,
+    UnexpectedValue,
+    InvalidValue,
+    UnknownPlaceholder, // 引用了未定义的占位符变量
+}
+
+/// 单个参数的校验问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlagIssue {
+    pub parameter_id: String,
+    pub flag: String,
+    pub severity: ValidationSeverity,
+    pub kind: FlagIssueKind,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+/// 参数校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlagValidationReport {
+    pub browser_version: Option<String>,
+    pub issues: Vec<FlagIssue>,
+    pub is_valid: bool, // 不存在 Error 级别的问题
+}
+
+/// 基于开关目录的启动参数校验器
+pub struct FlagValidator {
+    catalog: &'static SwitchCatalog,
+}
+
+impl Default for FlagValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FlagValidator {
+    pub fn new() -> Self {
+        Self {
+            catalog: SwitchCatalog::builtin(),
+        }
+    }
+
+    pub fn catalog(&self) -> &SwitchCatalog {
+        self.catalog
+    }
+
+    /// 校验一组启动参数（只校验启用的参数）
+    pub fn validate_parameters(
+        &self,
+        parameters: &[LaunchParameter],
+        browser_version: Option<&str>,
+    ) -> FlagValidationReport {
+        let major_version = browser_version.and_then(parse_major_version);
+
+        let issues: Vec<FlagIssue> = parameters
+            .iter()
+            .filter(|p| p.is_enabled)
+            .flat_map(|p| self.validate_parameter(p, major_version))
+            .collect();
+
+        FlagValidationReport {
+            browser_version: browser_version.map(str::to_string),
+            is_valid: !issues.iter().any(|i| i.severity == ValidationSeverity::Error),
+            issues,
+        }
+    }
+
+    /// 校验 Firefox 配置的参数：Chrome 开关目录不适用，只检查是否为 Firefox 识别的选项
+    pub fn validate_firefox_parameters(
+        &self,
+        parameters: &[LaunchParameter],
+        browser_version: Option<&str>,
+    ) -> FlagValidationReport {
+        let issues: Vec<FlagIssue> = parameters
+            .iter()
+            .filter(|p| p.is_enabled && !is_firefox_arg(&p.flag))
+            .map(|p| FlagIssue {
+                parameter_id: p.id.clone(),
+                flag: p.flag.clone(),
+                severity: ValidationSeverity::Warning,
+                kind: FlagIssueKind::UnknownSwitch,
+                message: format!("Firefox 不识别的选项 {}", split_flag(&p.flag).0),
+                suggestion: None,
+            })
+            .collect();
+
+        FlagValidationReport {
+            browser_version: browser_version.map(str::to_string),
+            is_valid: true,
+            issues,
+        }
+    }
+
+    /// 校验单个启动参数
+    pub fn validate_parameter(
+        &self,
+        parameter: &LaunchParameter,
+        major_version: Option<u32>,
+    ) -> Vec<FlagIssue> {
+        let mut issues = Vec::new();
+        let mut report = |severity, kind, message: String, suggestion: Option<String>| {
+            issues.push(FlagIssue {
+                parameter_id: parameter.id.clone(),
+                flag: parameter.flag.clone(),
+                severity,
+                kind,
+                message,
+                suggestion,
+            });
+        };
+
+        let (switch, inline_value) = split_flag(&parameter.flag);
+        let value = inline_value
+            .or(parameter.value.as_deref())
+            .filter(|v| !v.is_empty());
+        let name = switch.trim_start_matches('-');
+        let value_suffix = value.map(|v| format!("={}", v)).unwrap_or_default();
+
+        if !switch.starts_with("--") {
+            if switch.starts_with('-') && self.catalog.find(name).is_some() {
+                report(
+                    ValidationSeverity::Error,
+                    FlagIssueKind::Mistyped,
+                    "Chrome 开关需要使用 \"--\" 前缀".to_string(),
+                    Some(format!("--{}{}", name, value_suffix)),
+                );
+            } else {
+                report(
+                    ValidationSeverity::Info,
+                    FlagIssueKind::UnknownSwitch,
+                    "不是命令行开关，将作为普通参数传递".to_string(),
+                    None,
+                );
+            }
+            return issues;
+        }
+
+        // V8 参数（可能使用下划线写法）
+        if self.catalog.is_v8_flag(name) {
+            report(
+                ValidationSeverity::Error,
+                FlagIssueKind::MisplacedV8Flag,
+                format!("{} 是 V8 参数，不是 Chrome 开关", switch),
+                Some(format!("--js-flags=--{}{}", name.replace('_', "-"), value_suffix)),
+            );
+            return issues;
+        }
+
+        if name.contains('_') {
+            let dashed = name.replace('_', "-");
+            if self.catalog.find(&dashed).is_some() {
+                report(
+                    ValidationSeverity::Error,
+                    FlagIssueKind::Mistyped,
+                    "Chrome 开关使用连字符而不是下划线".to_string(),
+                    Some(format!("--{}{}", dashed, value_suffix)),
+                );
+                return issues;
+            }
+        }
+
+        let Some(definition) = self.catalog.find(name) else {
+            report(
+                ValidationSeverity::Warning,
+                FlagIssueKind::UnknownSwitch,
+                format!("未知的开关 {}", switch),
+                self.closest_switch(name).map(|s| s.flag()),
+            );
+            return issues;
+        };
+
+        // 版本兼容性
+        if let Some(removed_in) = definition.removed_in {
+            let removed = major_version.map(|v| v >= removed_in).unwrap_or(true);
+            if removed {
+                report(
+                    if major_version.is_some() {
+                        ValidationSeverity::Error
+                    } else {
+                        ValidationSeverity::Warning
+                    },
+                    FlagIssueKind::Obsolete,
+                    format!("{} 已在 Chrome {} 中移除", switch, removed_in),
+                    definition.replacement.clone(),
+                );
+            }
+        }
+        if let (Some(deprecated_since), Some(version)) = (definition.deprecated_since, major_version) {
+            if version >= deprecated_since && definition.removed_in.map(|r| version < r).unwrap_or(true) {
+                report(
+                    ValidationSeverity::Warning,
+                    FlagIssueKind::Deprecated,
+                    format!("{} 自 Chrome {} 起已废弃", switch, deprecated_since),
+                    definition.replacement.clone(),
+                );
+            }
+        }
+        if let (Some(min_version), Some(version)) = (definition.min_version, major_version) {
+            if version < min_version {
+                report(
+                    ValidationSeverity::Error,
+                    FlagIssueKind::NotYetSupported,
+                    format!("{} 需要 Chrome {} 或更高版本", switch, min_version),
+                    None,
+                );
+            }
+        }
+
+        // 参数值
+        match value {
+            None if definition.value_required => report(
+                ValidationSeverity::Error,
+                FlagIssueKind::MissingValue,
+                format!("{} 需要参数值", switch),
+                None,
+            ),
+            Some(_) if definition.value_type == SwitchValueType::None => report(
+                ValidationSeverity::Warning,
+                FlagIssueKind::UnexpectedValue,
+                format!("{} 不接受参数值", switch),
+                Some(switch.to_string()),
+            ),
+            // 含占位符的值在启动时才能确定
+            Some(value) if !find_placeholders(value).is_empty() => {}
+            Some(value) => {
+                if let Err(message) = validate_value(definition, value) {
+                    report(
+                        ValidationSeverity::Error,
+                        FlagIssueKind::InvalidValue,
+                        message,
+                        None,
+                    );
+                }
+            }
+            None => {}
+        }
+
+        issues
+    }
+
+    /// 检查参数中引用的占位符是否都已定义（只检查启用的参数）
+    pub fn validate_placeholders(
+        &self,
+        parameters: &[LaunchParameter],
+        known_variables: &[String],
+    ) -> Vec<FlagIssue> {
+        parameters
+            .iter()
+            .filter(|p| p.is_enabled)
+            .flat_map(|p| {
+                let text = format!("{}{}", p.flag, p.value.as_deref().unwrap_or_default());
+                find_placeholders(&text)
+                    .into_iter()
+                    .filter(|name| !known_variables.contains(name))
+                    .map(|name| FlagIssue {
+                        parameter_id: p.id.clone(),
+                        flag: p.flag.clone(),
+                        severity: ValidationSeverity::Error,
+                        kind: FlagIssueKind::UnknownPlaceholder,
+                        message: format!("未定义的占位符 {{{}}}", name),
+                        suggestion: None,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// 查找与给定名称最接近的已知开关（编辑距离不超过 2）
+    fn closest_switch(&self, name: &str) -> Option<&ChromeSwitch> {
+        self.catalog
+            .switches
+            .iter()
+            .map(|s| (edit_distance(&s.name, name), s))
+            .filter(|(distance, _)| *distance <= 2)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, s)| s)
+    }
+}
+
+/// 拆分 "--flag=value" 形式的标志
+pub fn split_flag(flag: &str) -> (&str, Option<&str>) {
+    match flag.split_once('=') {
+        Some((switch, value)) => (switch, Some(value)),
+        None => (flag, None),
+    }
+}
+
+/// 从版本号中提取主版本（如 "131.0.6778.85" -> 131）
+pub fn parse_major_version(version: &str) -> Option<u32> {
+    version
+        .trim()
+        .split(|c: char| !c.is_ascii_digit())
+        .next()
+        .and_then(|major| major.parse().ok())
+}
+
+fn validate_value(definition: &ChromeSwitch, value: &str) -> Result<(), String> {
+    let flag = definition.flag();
+    match definition.value_type {
+        SwitchValueType::Integer => {
+            let number: i64 = value
+                .parse()
+                .map_err(|_| format!("{} 的值必须是整数: {}", flag, value))?;
+            if definition.name.ends_with("-port") && !(0..=65535).contains(&number) {
+                return Err(format!("{} 的端口号超出范围: {}", flag, value));
+            }
+        }
+        SwitchValueType::Float => {
+            value
+                .parse::<f64>()
+                .map_err(|_| format!("{} 的值必须是数字: {}", flag, value))?;
+        }
+        SwitchValueType::Size => {
+            let valid = value
+                .split_once(',')
+                .map(|(a, b)| a.trim().parse::<i64>().is_ok() && b.trim().parse::<i64>().is_ok())
+                .unwrap_or(false);
+            if !valid {
+                return Err(format!("{} 的值必须是 \"宽,高\" 格式: {}", flag, value));
+            }
+        }
+        SwitchValueType::Enum => {
+            if !definition.allowed_values.iter().any(|v| v == value) {
+                return Err(format!(
+                    "{} 的值必须是 {} 之一: {}",
+                    flag,
+                    definition.allowed_values.join(", "),
+                    value
+                ));
+            }
+        }
+        SwitchValueType::Url => {
+            if !value.contains("://") && !value.starts_with("about:") && !value.starts_with("data:") {
+                return Err(format!("{} 的值必须是URL: {}", flag, value));
+            }
+        }
+        SwitchValueType::List => {
+            if value.split(',').any(|item| item.trim().is_empty()) {
+                return Err(format!("{} 的列表中存在空项: {}", flag, value));
+            }
+        }
+        SwitchValueType::String | SwitchValueType::Path | SwitchValueType::None => {}
+    }
+    Ok(())
+}
+
+/// 计算两个字符串的编辑距离
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b_chars.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            current.push((previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b_chars.len()]
+}
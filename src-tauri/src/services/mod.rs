@@ -3,29 +3,59 @@ use std::sync::Arc;
 use tauri::AppHandle;
 
 pub mod browser_manager;
+pub mod bundle_manager;
+pub mod capture_manager;
 pub mod download_manager;
+pub mod flag_validator;
 pub mod nodejs_runtime;
 pub mod retry_manager;
 pub mod parameter_manager;
+pub mod policy_manager;
+pub mod security_policy;
+pub mod storage_manager;
 
 pub use browser_manager::BrowserManager;
+pub use bundle_manager::BundleManager;
+pub use capture_manager::CaptureManager;
 pub use download_manager::DownloadManager;
+pub use flag_validator::FlagValidator;
 pub use nodejs_runtime::NodejsRuntime;
 pub use parameter_manager::ParameterManager;
+pub use policy_manager::PolicyManager;
+pub use security_policy::SecurityPolicyEngine;
+pub use storage_manager::StorageManager;
 
 #[derive(Clone)]
 pub struct AppState {
     pub browser_manager: Arc<BrowserManager>,
     pub download_manager: Arc<DownloadManager>,
     pub parameter_manager: Arc<ParameterManager>,
+    pub policy_manager: Arc<PolicyManager>,
+    pub bundle_manager: Arc<BundleManager>,
+    pub capture_manager: Arc<CaptureManager>,
+    pub security_policy: Arc<SecurityPolicyEngine>,
+    pub storage_manager: Arc<StorageManager>,
 }
 
 impl AppState {
     pub async fn new(database: Arc<Database>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         let nodejs_runtime = Arc::new(NodejsRuntime::new().await?);
         let download_manager = Arc::new(DownloadManager::new(nodejs_runtime.clone()));
-        let parameter_manager = Arc::new(ParameterManager::new(database.clone()));
-        let browser_manager = Arc::new(BrowserManager::new(database, download_manager.clone()));
+        let security_policy = Arc::new(SecurityPolicyEngine::load());
+        let parameter_manager = Arc::new(ParameterManager::new(database.clone(), security_policy.clone()));
+        let policy_manager = Arc::new(PolicyManager::new(database.clone(), parameter_manager.clone()));
+        let bundle_manager = Arc::new(BundleManager::new(database.clone(), parameter_manager.clone()));
+        let browser_manager = Arc::new(BrowserManager::new(
+            database.clone(),
+            download_manager.clone(),
+            security_policy.clone(),
+        ));
+        let capture_manager = Arc::new(CaptureManager::new(browser_manager.clone(), parameter_manager.clone()));
+        let storage_manager = Arc::new(StorageManager::new(
+            database,
+            browser_manager.clone(),
+            download_manager.clone(),
+        ));
 
         // Set up completion callback to save completed browsers to database
         {
@@ -38,10 +68,30 @@ impl AppState {
             }).await;
         }
 
+        // 定期永久删除回收站中过期的浏览器
+        {
+            let browser_manager = browser_manager.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(6 * 60 * 60));
+                let retention = chrono::Duration::days(browser_manager::TRASH_RETENTION_DAYS);
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = browser_manager.purge_trash(Some(retention)).await {
+                        tracing::warn!("Failed to purge browser trash: {}", e);
+                    }
+                }
+            });
+        }
+
         Ok(AppState {
             browser_manager,
             download_manager,
             parameter_manager,
+            policy_manager,
+            bundle_manager,
+            capture_manager,
+            security_policy,
+            storage_manager,
         })
     }
     
@@ -208,6 +208,39 @@ impl NodejsRuntime {
         }
     }
 
+    /// 将版本别名（如 stable、latest）解析为具体的 build ID
+    pub async fn resolve_build_id(&self, browser_type: &str, version: &str, platform: &str) -> Result<String, String> {
+        let script_path = self.get_script_path("resolve-version.js")?;
+
+        let output = Command::new(&self.node_path)
+            .arg("--no-warnings")
+            .arg(&script_path)
+            .arg("--browser")
+            .arg(browser_type)
+            .arg("--version")
+            .arg(version)
+            .arg("--platform")
+            .arg(platform)
+            .env("NODE_OPTIONS", "")
+            .output()
+            .await
+            .map_err(|e| format!("Failed to execute Node.js: {}", e))?;
+
+        if output.status.success() {
+            let output_str = String::from_utf8_lossy(&output.stdout);
+            let result: serde_json::Value = serde_json::from_str(&output_str)
+                .map_err(|e| format!("Failed to parse resolve result JSON: {}", e))?;
+
+            result["buildId"]
+                .as_str()
+                .map(|s| s.to_string())
+                .ok_or_else(|| "Missing buildId in resolve result".to_string())
+        } else {
+            let error = String::from_utf8_lossy(&output.stderr);
+            Err(format!("Node.js script failed: {}", error))
+        }
+    }
+
     pub async fn check_browser_installed(&self, browser_type: &str, version: &str, platform: &str) -> Result<bool, String> {
         let script_path = self.get_script_path("check-installation.js")?;
 
@@ -1,21 +1,47 @@
 use crate::database::Database;
-use crate::models::{BrowserLaunchConfig, LaunchParameter, ParameterTemplate, TemplateCategory};
-use std::collections::HashMap;
+use crate::services::flag_validator::{
+    parse_major_version, split_flag, FlagValidationReport, FlagValidator, ValidationSeverity,
+};
+use crate::services::security_policy::{SecurityPolicyEngine, SecurityValidation};
+use crate::models::{
+    BrowserFamily, BrowserInfo, BrowserLaunchConfig, BrowserType, ConfigConflictStrategy, ConfigDocumentFormat, ConfigLayer,
+    ConfigSource, LaunchArgs, LaunchArgsBuilder, LaunchArgsExplanation, LaunchConfigDocument,
+    LaunchConfigImportReport, LaunchParameter, LaunchVariableInfo, LayerExplanation, LayerState,
+    ParameterTemplate, PolicyDelivery, PolicySet, TemplateCategory, BUILTIN_LAUNCH_VARIABLES,
+};
+use crate::utils::{find_free_port, find_placeholders, is_firefox_arg, get_browser_key, get_launch_logs_dir, get_profiles_dir};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
 use tracing::{info, warn};
 
+/// 被禁用的参数层（JSON 数组）在 app_config 中的键
+const DISABLED_LAYERS_KEY: &str = "launch_layers.disabled";
+
+/// 参数层的应用顺序
+const LAYER_ORDER: [ConfigLayer; 4] = [
+    ConfigLayer::Global,
+    ConfigLayer::BrowserType,
+    ConfigLayer::Install,
+    ConfigLayer::AdHoc,
+];
+
 pub struct ParameterManager {
     database: Arc<Database>,
     cached_configs: Arc<tokio::sync::RwLock<HashMap<String, BrowserLaunchConfig>>>,
     builtin_templates: Vec<ParameterTemplate>,
+    flag_validator: FlagValidator,
+    security_policy: Arc<SecurityPolicyEngine>,
 }
 
 impl ParameterManager {
-    pub fn new(database: Arc<Database>) -> Self {
+    pub fn new(database: Arc<Database>, security_policy: Arc<SecurityPolicyEngine>) -> Self {
         Self {
             database,
             cached_configs: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
             builtin_templates: ParameterTemplate::get_builtin_templates(),
+            flag_validator: FlagValidator::new(),
+            security_policy,
         }
     }
 
@@ -33,15 +59,12 @@ impl ParameterManager {
         self.load_configs_from_database().await
     }
 
-    /// 根据浏览器ID获取配置
+    /// 根据浏览器ID获取配置（全局配置、同类型浏览器配置和该浏览器的配置）
     pub async fn get_configs_for_browser(&self, browser_id: &str) -> Result<Vec<BrowserLaunchConfig>, String> {
+        let browser = self.find_browser(browser_id).await?;
         let all_configs = self.get_all_configs().await?;
         
-        Ok(all_configs.into_iter().filter(|config| {
-            // 全局配置或特定浏览器配置
-            config.browser_id.is_none() || 
-            config.browser_id.as_ref() == Some(&browser_id.to_string())
-        }).collect())
+        Ok(all_configs.into_iter().filter(|config| config.applies_to(&browser)).collect())
     }
 
     /// 获取默认配置
@@ -58,10 +81,57 @@ impl ParameterManager {
     /// 保存配置
     pub async fn save_config(&self, mut config: BrowserLaunchConfig) -> Result<(), String> {
         config.updated_at = chrono::Utc::now();
+
+        // 开关本身已包含取值（如 "--lang=en"）时不能再单独指定取值
+        if let Some(parameter) = config.parameters.iter().find(|p| {
+            p.flag.contains('=') && p.value.as_deref().map(|v| !v.is_empty()).unwrap_or(false)
+        }) {
+            return Err(format!(
+                "Parameter {} already contains a value and cannot have a separate one",
+                parameter.flag
+            ));
+        }
+
+        // 检查自定义变量名
+        for name in config.variables.keys() {
+            if !crate::utils::is_valid_placeholder_name(name) {
+                return Err(format!("Invalid variable name: {}", name));
+            }
+            if BUILTIN_LAUNCH_VARIABLES.iter().any(|(builtin, _)| builtin == name) {
+                return Err(format!("Variable name is reserved: {}", name));
+            }
+        }
+
+        // 检查 Firefox 首选项的取值类型
+        for (name, value) in &config.firefox_prefs {
+            crate::utils::format_user_pref(name, value)?;
+        }
+        config.profile_seed.validate()?;
+
+        // 检查引用的企业策略集
+        if !config.policy_set_ids.is_empty() {
+            let policy_sets = self
+                .database
+                .get_policy_sets()
+                .await
+                .map_err(|e| format!("Failed to load policy sets: {}", e))?;
+            for set_id in &config.policy_set_ids {
+                if !policy_sets.iter().any(|set| &set.id == set_id) {
+                    return Err(format!("Policy set not found: {}", set_id));
+                }
+            }
+        }
+
+        // 检查继承关系
+        if config.extends.is_some() {
+            let mut all_configs = self.config_map().await?;
+            all_configs.insert(config.id.clone(), config.clone());
+            Self::expand_extends(&config, &all_configs)?;
+        }
         
         // 如果设为默认配置，需要清除其他默认配置
         if config.is_default {
-            self.clear_default_configs(&config.browser_id).await?;
+            self.clear_default_configs(&config).await?;
         }
 
         // 保存到数据库
@@ -81,6 +151,20 @@ impl ParameterManager {
 
     /// 删除配置
     pub async fn delete_config(&self, config_id: &str) -> Result<(), String> {
+        let children: Vec<String> = self
+            .get_all_configs()
+            .await?
+            .into_iter()
+            .filter(|c| c.extends.as_deref() == Some(config_id))
+            .map(|c| c.name)
+            .collect();
+        if !children.is_empty() {
+            return Err(format!(
+                "Configuration is extended by other configurations: {}",
+                children.join(", ")
+            ));
+        }
+
         // 从数据库删除
         self.delete_config_from_database(config_id).await?;
 
@@ -115,7 +199,18 @@ impl ParameterManager {
         name: String,
         browser_id: Option<String>,
     ) -> Result<BrowserLaunchConfig, String> {
-        let template = self.get_template(template_id)?;
+        let template = self.get_template(template_id).await?;
+        if let Some(browser_id) = &browser_id {
+            let browser = self.find_browser(browser_id).await?;
+            if !template.applies_to(&browser.browser_type) {
+                return Err(format!(
+                    "Template '{}' is for {} browsers and cannot be used with {}",
+                    template.name,
+                    template.browser_family.as_str(),
+                    browser.browser_type
+                ));
+            }
+        }
         
         let mut config = BrowserLaunchConfig::new(
             name,
@@ -183,93 +278,594 @@ impl ParameterManager {
             .ok_or_else(|| "Configuration not found".to_string())?;
 
         // 清除其他默认配置
-        self.clear_default_configs(&config.browser_id).await?;
+        self.clear_default_configs(&config).await?;
 
         // 设置为默认
         config.is_default = true;
         self.save_config(config).await
     }
 
-    /// 获取所有模板
-    pub fn get_all_templates(&self) -> Vec<ParameterTemplate> {
-        self.builtin_templates.clone()
+    /// 导出启动配置到 JSON/YAML 文件（格式由扩展名决定），None 表示导出全部
+    pub async fn export_configs(
+        &self,
+        config_ids: Option<Vec<String>>,
+        output_path: &Path,
+    ) -> Result<usize, String> {
+        let format = ConfigDocumentFormat::from_path(output_path)
+            .ok_or("Export file must use the .json, .yaml or .yml extension")?;
+
+        let mut configs = match config_ids {
+            Some(ids) => {
+                let mut configs = Vec::new();
+                for id in ids {
+                    let config = self
+                        .get_config(&id)
+                        .await?
+                        .ok_or_else(|| format!("Configuration not found: {}", id))?;
+                    configs.push(config);
+                }
+                configs
+            }
+            None => self.get_all_configs().await?,
+        };
+        // 按名称排序，保证导出结果稳定，便于纳入版本管理
+        configs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        // 策略集只存在于本机，导出后引用会失效
+        for config in configs.iter().filter(|c| !c.policy_set_ids.is_empty()) {
+            warn!(
+                "Policy sets referenced by launch configuration {} are not exported",
+                config.name
+            );
+        }
+
+        let all_configs = self.config_map().await?;
+        let content = LaunchConfigDocument::new(&configs, &all_configs).to_text(format)?;
+        tokio::fs::write(output_path, content)
+            .await
+            .map_err(|e| format!("Failed to write export file: {}", e))?;
+
+        info!("Exported {} launch configurations to {:?}", configs.len(), output_path);
+        Ok(configs.len())
+    }
+
+    /// 从 JSON/YAML 文件导入启动配置
+    pub async fn import_configs(
+        &self,
+        input_path: &Path,
+        strategy: ConfigConflictStrategy,
+        browser_id: Option<String>,
+    ) -> Result<LaunchConfigImportReport, String> {
+        let format = ConfigDocumentFormat::from_path(input_path)
+            .ok_or("Import file must use the .json, .yaml or .yml extension")?;
+        let content = tokio::fs::read_to_string(input_path)
+            .await
+            .map_err(|e| format!("Failed to read import file: {}", e))?;
+        let document = LaunchConfigDocument::parse(&content, format)?;
+
+        // 导入中途失败时恢复已保存的配置，避免留下部分导入的结果
+        let mut saved = Vec::new();
+        match self
+            .import_document(document, strategy, browser_id, &mut saved)
+            .await
+        {
+            Ok(report) => {
+                info!(
+                    "Imported {} launch configurations from {:?} ({} skipped, {} warnings)",
+                    report.imported.len(),
+                    input_path,
+                    report.skipped.len(),
+                    report.warnings.len()
+                );
+                Ok(report)
+            }
+            Err(e) => {
+                self.restore_configs(saved).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// 保存文档中的配置，每个被保存配置的ID及保存前的内容（新建时为 None）记录在 saved 中
+    async fn import_document(
+        &self,
+        document: LaunchConfigDocument,
+        strategy: ConfigConflictStrategy,
+        browser_id: Option<String>,
+        saved: &mut Vec<(String, Option<BrowserLaunchConfig>)>,
+    ) -> Result<LaunchConfigImportReport, String> {
+        let mut existing = self.get_all_configs().await?;
+        let mut report = LaunchConfigImportReport {
+            imported: Vec::new(),
+            skipped: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        // 文档中的配置名称 -> 导入后的配置ID，以及待解析的父配置名称
+        let mut imported_ids: HashMap<String, String> = HashMap::new();
+        let mut pending_parents: Vec<(usize, String)> = Vec::new();
+
+        for portable in document.configs {
+            let document_name = portable.name.clone();
+            let parent_name = portable.extends.clone();
+            let mut config = portable.into_config(browser_id.clone());
+
+            // 不信任文件中的危险标记，按开关目录和安全策略重新判定（包括单横线等写法）
+            for parameter in config.parameters.iter_mut() {
+                if !parameter.is_dangerous
+                    && !self.security_policy.findings(&[parameter.to_arg()]).is_empty()
+                {
+                    parameter.is_dangerous = true;
+                }
+                if parameter.is_dangerous && parameter.is_enabled {
+                    report.warnings.push(format!(
+                        "配置 '{}' 包含危险参数: {} ({})",
+                        config.name, parameter.name, parameter.flag
+                    ));
+                }
+            }
+
+            let mut previous = None;
+            if let Some(current) = existing.iter().find(|c| c.name == config.name).cloned() {
+                match strategy {
+                    ConfigConflictStrategy::Skip => {
+                        report
+                            .skipped
+                            .push(format!("Configuration '{}' already exists", config.name));
+                        continue;
+                    }
+                    ConfigConflictStrategy::Overwrite => {
+                        // 保留原ID和默认状态，使已有引用继续有效
+                        config.id = current.id.clone();
+                        config.is_default = current.is_default;
+                        config.created_at = current.created_at;
+                        if browser_id.is_none() {
+                            config.browser_id = current.browser_id.clone();
+                        }
+                        existing.retain(|c| c.id != current.id);
+                        previous = Some(current);
+                    }
+                    ConfigConflictStrategy::Rename => {
+                        config.name = Self::unique_config_name(&config.name, &existing);
+                    }
+                }
+            }
+
+            saved.push((config.id.clone(), previous));
+            self.save_config(config.clone()).await?;
+            imported_ids.insert(document_name, config.id.clone());
+            if let Some(parent_name) = parent_name {
+                pending_parents.push((report.imported.len(), parent_name));
+            }
+            existing.push(config.clone());
+            report.imported.push(config);
+        }
+
+        // 继承关系按名称解析：优先匹配同一文档中导入的配置，其次是本机已有的同名配置
+        for (index, parent_name) in pending_parents {
+            let parent_id = imported_ids
+                .get(&parent_name)
+                .cloned()
+                .or_else(|| existing.iter().find(|c| c.name == parent_name).map(|c| c.id.clone()));
+            let config = &mut report.imported[index];
+            match parent_id {
+                Some(parent_id) => {
+                    config.extends = Some(parent_id);
+                    self.save_config(config.clone()).await?;
+                }
+                None => report.warnings.push(format!(
+                    "配置 '{}' 继承的配置 '{}' 不存在，已忽略继承关系",
+                    config.name, parent_name
+                )),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 撤销导入：删除新建的配置，恢复被覆盖的配置
+    async fn restore_configs(&self, saved: Vec<(String, Option<BrowserLaunchConfig>)>) {
+        for (config_id, previous) in saved.into_iter().rev() {
+            let result = match &previous {
+                Some(config) => self.save_config_to_database(config).await,
+                None => self.delete_config_from_database(&config_id).await,
+            };
+            if let Err(e) = result {
+                warn!("Failed to roll back imported launch configuration {}: {}", config_id, e);
+                continue;
+            }
+
+            let mut cache = self.cached_configs.write().await;
+            match previous {
+                Some(config) => cache.insert(config_id, config),
+                None => cache.remove(&config_id),
+            };
+        }
+    }
+
+    /// 获取所有模板（内置模板在前，用户模板在后）
+    pub async fn get_all_templates(&self) -> Result<Vec<ParameterTemplate>, String> {
+        let mut templates = self.builtin_templates.clone();
+        templates.extend(self.get_user_templates().await?);
+        Ok(templates)
+    }
+
+    /// 获取适用于指定浏览器类型的模板
+    pub async fn get_templates_for_browser_type(
+        &self,
+        browser_type: &BrowserType,
+    ) -> Result<Vec<ParameterTemplate>, String> {
+        Ok(self
+            .get_all_templates()
+            .await?
+            .into_iter()
+            .filter(|t| t.applies_to(browser_type))
+            .collect())
     }
 
     /// 根据分类获取模板
-    pub fn get_templates_by_category(&self, category: TemplateCategory) -> Vec<ParameterTemplate> {
-        self.builtin_templates
-            .iter()
+    pub async fn get_templates_by_category(
+        &self,
+        category: TemplateCategory,
+    ) -> Result<Vec<ParameterTemplate>, String> {
+        Ok(self
+            .get_all_templates()
+            .await?
+            .into_iter()
             .filter(|t| t.category == category)
-            .cloned()
-            .collect()
+            .collect())
     }
 
     /// 获取单个模板
-    pub fn get_template(&self, template_id: &str) -> Result<ParameterTemplate, String> {
-        self.builtin_templates
-            .iter()
+    pub async fn get_template(&self, template_id: &str) -> Result<ParameterTemplate, String> {
+        if let Some(template) = self.builtin_templates.iter().find(|t| t.id == template_id) {
+            return Ok(template.clone());
+        }
+
+        self.get_user_templates()
+            .await?
+            .into_iter()
             .find(|t| t.id == template_id)
-            .cloned()
             .ok_or_else(|| "Template not found".to_string())
     }
 
+    /// 从已有配置创建用户模板
+    pub async fn create_template_from_config(
+        &self,
+        config_id: &str,
+        name: String,
+        description: String,
+        category: TemplateCategory,
+    ) -> Result<ParameterTemplate, String> {
+        let config = self.get_config(config_id).await?
+            .ok_or_else(|| "Configuration not found".to_string())?;
+
+        // 模板的内核家族取自配置的作用域，全局配置按 Chromium 处理
+        let browser_family = match (&config.browser_id, &config.browser_type) {
+            (Some(browser_id), _) => self.find_browser(browser_id).await?.browser_type.family(),
+            (None, Some(browser_type)) => browser_type.family(),
+            (None, None) => BrowserFamily::Chromium,
+        };
+
+        let template = ParameterTemplate {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            description,
+            category,
+            browser_family,
+            // 模板中的参数使用新的ID，与原配置解耦
+            parameters: config
+                .parameters
+                .into_iter()
+                .map(|mut p| {
+                    p.id = uuid::Uuid::new_v4().to_string();
+                    p
+                })
+                .collect(),
+            is_builtin: false,
+        };
+
+        self.database
+            .save_template(&template)
+            .await
+            .map_err(|e| format!("Failed to save template: {}", e))?;
+
+        info!("Created parameter template {} from configuration {}", template.id, config_id);
+        Ok(template)
+    }
+
+    /// 更新用户模板（内置模板不可修改）
+    pub async fn update_template(&self, mut template: ParameterTemplate) -> Result<(), String> {
+        self.ensure_user_template(&template.id).await?;
+
+        template.is_builtin = false;
+        self.database
+            .save_template(&template)
+            .await
+            .map_err(|e| format!("Failed to save template: {}", e))?;
+
+        info!("Updated parameter template: {}", template.id);
+        Ok(())
+    }
+
+    /// 删除用户模板（内置模板不可删除）
+    pub async fn delete_template(&self, template_id: &str) -> Result<(), String> {
+        self.ensure_user_template(template_id).await?;
+
+        self.database
+            .delete_template(template_id)
+            .await
+            .map_err(|e| format!("Failed to delete template: {}", e))?;
+
+        info!("Deleted parameter template: {}", template_id);
+        Ok(())
+    }
+
     /// 组合多个配置的参数为命令行参数
     pub async fn build_launch_args(
         &self, 
         browser_id: &str, 
         config_ids: Option<Vec<String>>
     ) -> Result<Vec<String>, String> {
-        let configs = if let Some(ids) = config_ids {
-            // 使用指定的配置
+        Ok(self.resolve_launch_args(browser_id, config_ids, None).await?.args)
+    }
+
+    /// 合并各层配置及附加参数，返回最终参数及覆盖情况
+    pub async fn resolve_launch_args(
+        &self,
+        browser_id: &str,
+        config_ids: Option<Vec<String>>,
+        extra_args: Option<Vec<String>>,
+    ) -> Result<LaunchArgs, String> {
+        self.resolve_launch_args_with(browser_id, config_ids, extra_args, &[]).await
+    }
+
+    /// 同 resolve_launch_args，并在最后应用本次启动必需的参数
+    ///
+    /// required_args（如隔离的用户数据目录、调试端口）不受参数层启用状态影响，总会生效。
+    pub async fn resolve_launch_args_with(
+        &self,
+        browser_id: &str,
+        config_ids: Option<Vec<String>>,
+        extra_args: Option<Vec<String>>,
+        required_args: &[String],
+    ) -> Result<LaunchArgs, String> {
+        let (explanation, config_placeholders) = self
+            .merge_launch_args(browser_id, config_ids, extra_args, required_args)
+            .await?;
+        let resolved = explanation.resolved;
+
+        // 已保存的配置中引用了未定义的占位符视为配置错误；
+        // 临时参数和 URL 中的花括号可能是字面内容，原样保留（合并时已记录警告）
+        let unknown_in_configs: Vec<&str> = resolved
+            .unresolved_placeholders
+            .iter()
+            .filter(|name| config_placeholders.contains(*name))
+            .map(String::as_str)
+            .collect();
+        if !unknown_in_configs.is_empty() {
+            return Err(format!(
+                "Unknown placeholders in launch configurations: {}",
+                unknown_in_configs.join(", ")
+            ));
+        }
+
+        Ok(resolved)
+    }
+
+    /// 按层合并启动参数并说明每个参数的来源
+    ///
+    /// 各层依次为：全局默认配置 → 浏览器类型默认配置 → 该浏览器的默认配置 →
+    /// 临时指定的配置和参数，后面的层覆盖前面的层。配置通过 extends 继承时，
+    /// 父配置的参数先于子配置应用。
+    pub async fn explain_launch_args(
+        &self,
+        browser_id: &str,
+        config_ids: Option<Vec<String>>,
+        extra_args: Option<Vec<String>>,
+    ) -> Result<LaunchArgsExplanation, String> {
+        Ok(self
+            .merge_launch_args(browser_id, config_ids, extra_args, &[])
+            .await?
+            .0)
+    }
+
+    /// 合并启动参数，同时返回已应用的配置中引用的占位符
+    async fn merge_launch_args(
+        &self,
+        browser_id: &str,
+        config_ids: Option<Vec<String>>,
+        extra_args: Option<Vec<String>>,
+        required_args: &[String],
+    ) -> Result<(LaunchArgsExplanation, HashSet<String>), String> {
+        let browser = self.find_browser(browser_id).await?;
+        let all_configs = self.config_map().await?;
+        let layer_states = self.get_layer_states().await?;
+        let policy_sets: HashMap<String, PolicySet> = self
+            .database
+            .get_policy_sets()
+            .await
+            .map_err(|e| format!("Failed to load policy sets: {}", e))?
+            .into_iter()
+            .map(|set| (set.id.clone(), set))
+            .collect();
+        // 不支持按次启动应用策略的构建跳过策略集，其余设置照常生效
+        let policies_supported = PolicyDelivery::for_browser(&browser.browser_type).is_some();
+
+        let mut builder = LaunchArgsBuilder::new();
+        let mut layers = Vec::new();
+        let mut applied_configs: Vec<String> = Vec::new();
+        let mut config_placeholders: HashSet<String> = HashSet::new();
+
+        for layer in LAYER_ORDER {
+            let enabled = layer_states
+                .iter()
+                .any(|state| state.layer == layer && state.enabled);
+
+            let roots: Vec<&BrowserLaunchConfig> = if layer == ConfigLayer::AdHoc {
+                config_ids
+                    .iter()
+                    .flatten()
+                    .filter_map(|id| {
+                        let config = all_configs.get(id);
+                        if config.is_none() {
+                            warn!("Launch configuration not found: {}", id);
+                        }
+                        config
+                    })
+                    .collect()
+            } else {
+                // 限定版本范围的默认配置比不限版本的更具体，后应用
+                let mut defaults: Vec<&BrowserLaunchConfig> = all_configs
+                    .values()
+                    .filter(|c| c.is_default && c.layer() == layer && c.applies_to(&browser))
+                    .collect();
+                defaults.sort_by_key(|c| (c.version_range.is_some(), c.created_at));
+                defaults
+            };
+
             let mut configs = Vec::new();
-            for id in ids {
-                if let Some(config) = self.get_config(&id).await? {
-                    if config.is_enabled {
-                        configs.push(config);
+            for root in roots {
+                for (config, inherited_by) in Self::expand_extends(root, &all_configs)? {
+                    if !config.is_enabled {
+                        continue;
+                    }
+                    if enabled {
+                        config_placeholders.extend(Self::config_placeholders(config));
+                        let mut args = config.to_command_args();
+                        // 全局配置不区分浏览器类型，Firefox 只接收它识别的选项
+                        if layer == ConfigLayer::Global && browser.browser_type.family() == BrowserFamily::Firefox {
+                            let (accepted, skipped): (Vec<String>, Vec<String>) =
+                                args.into_iter().partition(|arg| is_firefox_arg(arg));
+                            if !skipped.is_empty() {
+                                warn!(
+                                    "Skipping arguments of launch configuration {} not supported by {}: {:?}",
+                                    config.id, browser.browser_type, skipped
+                                );
+                            }
+                            args = accepted;
+                        }
+                        builder.push_args(&args, &config.id);
+                        for (key, value) in &config.env {
+                            builder.set_env(key, value, &config.id);
+                        }
+                        if let Some(dir) = &config.working_dir {
+                            builder.set_working_dir(dir, &config.id);
+                        }
+                        for (name, value) in &config.variables {
+                            builder.set_variable(name, value, &config.id);
+                        }
+                        for (name, value) in &config.firefox_prefs {
+                            builder.set_firefox_pref(name, value, &config.id);
+                        }
+                        for (path, value) in &config.profile_seed.preferences {
+                            builder.set_preference(path, value, &config.id);
+                        }
+                        for (path, value) in &config.profile_seed.local_state {
+                            builder.set_local_state(path, value, &config.id);
+                        }
+                        for entry in &config.profile_seed.enabled_experiments {
+                            builder.enable_experiment(entry, &config.id);
+                        }
+                        if !policies_supported && !config.policy_set_ids.is_empty() {
+                            warn!(
+                                "Skipping policy sets of launch configuration {}: {} builds do not support enterprise policies",
+                                config.id, browser.browser_type
+                            );
+                        } else {
+                            for set_id in &config.policy_set_ids {
+                                match policy_sets.get(set_id) {
+                                    Some(set) => {
+                                        for (name, value) in &set.policies {
+                                            builder.set_policy(name, value, &config.id);
+                                        }
+                                    }
+                                    None => warn!("Policy set not found: {}", set_id),
+                                }
+                            }
+                        }
                     }
+                    if enabled && !applied_configs.contains(&config.id) {
+                        applied_configs.push(config.id.clone());
+                    }
+                    configs.push(ConfigSource {
+                        config_id: config.id.clone(),
+                        config_name: config.name.clone(),
+                        inherited_by,
+                    });
                 }
             }
-            configs
-        } else {
-            // 使用默认配置
-            if let Some(default_config) = self.get_default_config(Some(browser_id)).await? {
-                vec![default_config]
-            } else {
-                // 如果没有默认配置，使用全局默认配置
-                if let Some(global_default) = self.get_default_config(None).await? {
-                    vec![global_default]
-                } else {
-                    Vec::new()
+
+            if layer == ConfigLayer::AdHoc && enabled {
+                if let Some(extra_args) = &extra_args {
+                    builder.push_args(extra_args, "user");
                 }
             }
-        };
 
-        // 收集所有启用的参数
-        let mut all_args = Vec::new();
-        for config in configs {
-            if config.is_enabled {
-                let args = config.to_command_args();
-                all_args.extend(args);
-            }
+            layers.push(LayerExplanation {
+                layer,
+                enabled,
+                configs,
+            });
         }
 
-        // 去重（保留最后一个重复的参数）
-        let mut unique_args = Vec::new();
-        let mut seen_flags = std::collections::HashSet::new();
-        
-        for arg in all_args.iter().rev() {
-            if arg.starts_with("--") {
-                if !seen_flags.contains(arg) {
-                    seen_flags.insert(arg.clone());
-                    unique_args.insert(0, arg.clone());
-                }
-            } else {
-                unique_args.insert(0, arg.clone());
-            }
+        builder.push_args(required_args, "launch");
+
+        let mut resolved = builder.build();
+        resolved.config_ids = applied_configs;
+        let builtins = Self::builtin_variables(&browser, &resolved.placeholders())?;
+        resolved.substitute(&builtins);
+        if !resolved.unresolved_placeholders.is_empty() {
+            warn!("Unknown placeholders in launch arguments: {:?}", resolved.unresolved_placeholders);
+        }
+
+        for item in &resolved.overrides {
+            info!(
+                "Launch argument {} overridden: {:?} ({}) -> {:?} ({})",
+                item.switch, item.previous_value, item.previous_source, item.new_value, item.new_source
+            );
+        }
+
+        info!("Built launch arguments for browser {}: {:?}", browser_id, resolved.args);
+        Ok((
+            LaunchArgsExplanation {
+                browser_id: browser_id.to_string(),
+                layers,
+                resolved,
+            },
+            config_placeholders,
+        ))
+    }
+
+    /// 获取各参数层的启用状态
+    pub async fn get_layer_states(&self) -> Result<Vec<LayerState>, String> {
+        let disabled = self.disabled_layers().await?;
+        Ok(LAYER_ORDER
+            .iter()
+            .map(|layer| LayerState {
+                layer: *layer,
+                enabled: !disabled.contains(layer),
+            })
+            .collect())
+    }
+
+    /// 启用或禁用某个参数层
+    pub async fn set_layer_enabled(&self, layer: ConfigLayer, enabled: bool) -> Result<(), String> {
+        let mut disabled = self.disabled_layers().await?;
+        disabled.retain(|l| *l != layer);
+        if !enabled {
+            disabled.push(layer);
         }
 
-        info!("Built launch arguments for browser {}: {:?}", browser_id, unique_args);
-        Ok(unique_args)
+        let value = serde_json::to_string(&disabled)
+            .map_err(|e| format!("Failed to serialize layer states: {}", e))?;
+        self.database
+            .set_config(DISABLED_LAYERS_KEY, &value)
+            .await
+            .map_err(|e| format!("Failed to save layer states: {}", e))?;
+
+        info!("Launch parameter layer {:?} enabled: {}", layer, enabled);
+        Ok(())
     }
 
     /// 验证配置安全性
@@ -277,26 +873,233 @@ impl ParameterManager {
         let config = self.get_config(config_id).await?
             .ok_or_else(|| "Configuration not found".to_string())?;
 
-        let dangerous_params: Vec<&LaunchParameter> = config
-            .get_enabled_parameters()
-            .into_iter()
-            .filter(|p| p.is_dangerous)
-            .collect();
+        let mut findings = self.security_policy.findings(&config.to_command_args());
 
-        let validation = SecurityValidation {
-            is_safe: dangerous_params.is_empty(),
-            dangerous_parameter_count: dangerous_params.len(),
-            warnings: dangerous_params
-                .into_iter()
-                .map(|p| format!("危险参数: {} - {}", p.name, p.description))
-                .collect(),
+        // 用户标记为危险、但规则未覆盖的参数
+        for parameter in config.get_enabled_parameters().into_iter().filter(|p| p.is_dangerous) {
+            let (switch, _) = split_flag(&parameter.flag);
+            if !findings.iter().any(|f| f.flag == switch) {
+                findings.push(SecurityPolicyEngine::dangerous_flag_finding(
+                    switch,
+                    &parameter.description,
+                ));
+            }
+        }
+
+        Ok(self.security_policy.summarize(findings))
+    }
+
+    /// 根据开关目录校验配置中的参数，指定浏览器时按其版本检查兼容性
+    pub async fn validate_config_flags(
+        &self,
+        config_id: &str,
+        browser_id: Option<&str>,
+    ) -> Result<FlagValidationReport, String> {
+        let config = self.get_config(config_id).await?
+            .ok_or_else(|| "Configuration not found".to_string())?;
+
+        let browser = match browser_id {
+            Some(browser_id) => Some(self.find_browser(browser_id).await?),
+            None => None,
+        };
+        let browser_version = browser.as_ref().map(|b| b.version.clone());
+
+        // Firefox 配置不能按 Chrome 开关目录校验
+        let family = match (&browser, &config.browser_type, &config.browser_id) {
+            (Some(browser), _, _) => browser.browser_type.family(),
+            (None, Some(browser_type), _) => browser_type.family(),
+            (None, None, Some(config_browser_id)) => self
+                .find_browser(config_browser_id)
+                .await
+                .map(|b| b.browser_type.family())
+                .unwrap_or_default(),
+            (None, None, None) => BrowserFamily::Chromium,
         };
+        let mut report = match family {
+            BrowserFamily::Chromium => self
+                .flag_validator
+                .validate_parameters(&config.parameters, browser_version.as_deref()),
+            BrowserFamily::Firefox => self
+                .flag_validator
+                .validate_firefox_parameters(&config.parameters, browser_version.as_deref()),
+        };
+
+        // 占位符只能引用内置变量和继承链上定义的变量
+        let all_configs = self.config_map().await?;
+        let mut known_variables: Vec<String> = BUILTIN_LAUNCH_VARIABLES
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect();
+        for (chain_config, _) in Self::expand_extends(&config, &all_configs)? {
+            known_variables.extend(chain_config.variables.keys().cloned());
+        }
+
+        report.issues.extend(
+            self.flag_validator
+                .validate_placeholders(&config.parameters, &known_variables),
+        );
+        report.is_valid = !report
+            .issues
+            .iter()
+            .any(|i| i.severity == ValidationSeverity::Error);
+
+        Ok(report)
+    }
 
-        Ok(validation)
+    /// 内置变量列表
+    pub fn builtin_variable_info(&self) -> Vec<LaunchVariableInfo> {
+        BUILTIN_LAUNCH_VARIABLES
+            .iter()
+            .map(|(name, description)| LaunchVariableInfo {
+                name: name.to_string(),
+                description: description.to_string(),
+            })
+            .collect()
     }
 
     // 私有方法
 
+    async fn find_browser(&self, browser_id: &str) -> Result<BrowserInfo, String> {
+        self.database
+            .get_browsers()
+            .await
+            .map_err(|e| format!("Failed to get browsers: {}", e))?
+            .into_iter()
+            .find(|b| b.id == browser_id)
+            .ok_or_else(|| "Browser not found".to_string())
+    }
+
+    /// 计算内置变量的值（只有被引用时才分配空闲端口）
+    fn builtin_variables(
+        browser: &BrowserInfo,
+        used: &[String],
+    ) -> Result<BTreeMap<String, String>, String> {
+        let path = |p: &Path| p.to_string_lossy().to_string();
+        let mut variables = BTreeMap::from([
+            ("browser_id".to_string(), browser.id.clone()),
+            (
+                "browser_type".to_string(),
+                get_browser_key(&browser.browser_type).to_string(),
+            ),
+            ("version".to_string(), browser.version.clone()),
+            (
+                "major_version".to_string(),
+                parse_major_version(&browser.version)
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+            ),
+            ("platform".to_string(), browser.platform.to_string()),
+            ("install_path".to_string(), path(&browser.install_path)),
+            ("executable_path".to_string(), path(&browser.executable_path)),
+            (
+                "profile_dir".to_string(),
+                path(&get_profiles_dir()?.join(&browser.id)),
+            ),
+            ("logs_dir".to_string(), path(&get_launch_logs_dir()?)),
+            ("temp_dir".to_string(), path(&std::env::temp_dir())),
+            (
+                "timestamp".to_string(),
+                chrono::Local::now().format("%Y%m%d-%H%M%S").to_string(),
+            ),
+        ]);
+
+        if used.iter().any(|name| name == "free_port") {
+            variables.insert("free_port".to_string(), find_free_port()?.to_string());
+        }
+
+        Ok(variables)
+    }
+
+    async fn config_map(&self) -> Result<HashMap<String, BrowserLaunchConfig>, String> {
+        Ok(self
+            .get_all_configs()
+            .await?
+            .into_iter()
+            .map(|c| (c.id.clone(), c))
+            .collect())
+    }
+
+    async fn disabled_layers(&self) -> Result<Vec<ConfigLayer>, String> {
+        let value = self
+            .database
+            .get_config(DISABLED_LAYERS_KEY)
+            .await
+            .map_err(|e| format!("Failed to load layer states: {}", e))?;
+
+        Ok(value
+            .and_then(|v| serde_json::from_str(&v).ok())
+            .unwrap_or_default())
+    }
+
+    /// 展开继承链，返回从最顶层父配置到该配置本身的列表（附带引入它的子配置ID）
+    fn expand_extends<'a>(
+        config: &'a BrowserLaunchConfig,
+        all_configs: &'a HashMap<String, BrowserLaunchConfig>,
+    ) -> Result<Vec<(&'a BrowserLaunchConfig, Option<String>)>, String> {
+        let mut chain = vec![(config, None)];
+        let mut visited = HashSet::from([config.id.as_str()]);
+        let mut current = config;
+
+        while let Some(parent_id) = &current.extends {
+            if !visited.insert(parent_id.as_str()) {
+                return Err(format!("Circular configuration inheritance: {}", config.name));
+            }
+            let parent = all_configs
+                .get(parent_id)
+                .ok_or_else(|| format!("Parent configuration not found: {}", parent_id))?;
+            chain.push((parent, Some(current.id.clone())));
+            current = parent;
+        }
+
+        chain.reverse();
+        Ok(chain)
+    }
+
+    async fn get_user_templates(&self) -> Result<Vec<ParameterTemplate>, String> {
+        self.database
+            .get_templates()
+            .await
+            .map_err(|e| format!("Failed to load templates: {}", e))
+    }
+
+    async fn ensure_user_template(&self, template_id: &str) -> Result<(), String> {
+        if self.builtin_templates.iter().any(|t| t.id == template_id) {
+            return Err("Built-in templates cannot be modified".to_string());
+        }
+
+        if !self.get_user_templates().await?.iter().any(|t| t.id == template_id) {
+            return Err("Template not found".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// 配置的参数、环境变量、工作目录、自定义变量和字符串类型的首选项中引用的占位符
+    fn config_placeholders(config: &BrowserLaunchConfig) -> Vec<String> {
+        let strings = config
+            .firefox_prefs
+            .values()
+            .chain(config.profile_seed.preferences.values())
+            .chain(config.profile_seed.local_state.values())
+            .filter_map(|v| v.as_str().map(str::to_string));
+        config
+            .to_command_args()
+            .into_iter()
+            .chain(config.env.values().cloned())
+            .chain(config.working_dir.iter().cloned())
+            .chain(config.variables.values().cloned())
+            .chain(strings)
+            .flat_map(|text| find_placeholders(&text))
+            .collect()
+    }
+
+    fn unique_config_name(name: &str, existing: &[BrowserLaunchConfig]) -> String {
+        (2..)
+            .map(|n| format!("{} ({})", name, n))
+            .find(|candidate| !existing.iter().any(|c| &c.name == candidate))
+            .unwrap_or_else(|| name.to_string())
+    }
+
     async fn load_configs_from_database(&self) -> Result<Vec<BrowserLaunchConfig>, String> {
         // TODO: 实现从数据库加载配置
         // 目前返回空列表，实际实现需要添加数据库表和查询
@@ -316,11 +1119,11 @@ impl ParameterManager {
         Ok(())
     }
 
-    async fn clear_default_configs(&self, browser_id: &Option<String>) -> Result<(), String> {
+    async fn clear_default_configs(&self, target: &BrowserLaunchConfig) -> Result<(), String> {
         let all_configs = self.get_all_configs().await?;
         
         for mut config in all_configs {
-            if config.is_default && config.browser_id == *browser_id {
+            if config.is_default && config.id != target.id && config.same_scope(target) {
                 config.is_default = false;
                 self.save_config_to_database(&config).await?;
                 
@@ -333,17 +1136,3 @@ impl ParameterManager {
         Ok(())
     }
 }
-
-/// 安全性验证结果
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct SecurityValidation {
-    pub is_safe: bool,
-    pub dangerous_parameter_count: usize,
-    pub warnings: Vec<String>,
-}
-
-impl SecurityValidation {
-    pub fn has_critical_warnings(&self) -> bool {
-        self.dangerous_parameter_count > 3
-    }
-}
\ No newline at end of file
@@ -0,0 +1,236 @@
+use crate::database::Database;
+use crate::models::{PolicyCatalog, PolicyDefinition, PolicySet, PolicyValueType};
+use crate::services::flag_validator::{edit_distance, ValidationSeverity};
+use crate::services::ParameterManager;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::info;
+
+/// 策略校验问题类型
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum PolicyIssueKind {
+    UnknownPolicy, // 目录中不存在的策略
+    InvalidType,   // 取值类型与策略定义不符
+    InvalidValue,  // 取值不在允许范围内
+    Deprecated,    // 已废弃
+}
+
+/// 单个策略的校验问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyIssue {
+    pub policy: String,
+    pub severity: ValidationSeverity,
+    pub kind: PolicyIssueKind,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+/// 策略校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyValidationReport {
+    pub issues: Vec<PolicyIssue>,
+    pub is_valid: bool, // 不存在 Error 级别的问题
+}
+
+/// 企业策略集管理器
+pub struct PolicyManager {
+    database: Arc<Database>,
+    parameter_manager: Arc<ParameterManager>,
+    catalog: &'static PolicyCatalog,
+}
+
+impl PolicyManager {
+    pub fn new(database: Arc<Database>, parameter_manager: Arc<ParameterManager>) -> Self {
+        Self {
+            database,
+            parameter_manager,
+            catalog: PolicyCatalog::builtin(),
+        }
+    }
+
+    pub fn catalog(&self) -> &PolicyCatalog {
+        self.catalog
+    }
+
+    /// 获取所有策略集
+    pub async fn get_policy_sets(&self) -> Result<Vec<PolicySet>, String> {
+        self.database
+            .get_policy_sets()
+            .await
+            .map_err(|e| format!("Failed to load policy sets: {}", e))
+    }
+
+    /// 获取单个策略集
+    pub async fn get_policy_set(&self, policy_set_id: &str) -> Result<PolicySet, String> {
+        self.get_policy_sets()
+            .await?
+            .into_iter()
+            .find(|set| set.id == policy_set_id)
+            .ok_or_else(|| "Policy set not found".to_string())
+    }
+
+    /// 创建策略集
+    pub async fn create_policy_set(
+        &self,
+        name: String,
+        description: String,
+        policies: BTreeMap<String, serde_json::Value>,
+    ) -> Result<PolicySet, String> {
+        let mut policy_set = PolicySet::new(name, description);
+        policy_set.policies = policies;
+        self.save_policy_set(&policy_set).await?;
+
+        info!("Created policy set: {}", policy_set.id);
+        Ok(policy_set)
+    }
+
+    /// 更新策略集
+    pub async fn update_policy_set(&self, mut policy_set: PolicySet) -> Result<PolicySet, String> {
+        let existing = self.get_policy_set(&policy_set.id).await?;
+        policy_set.created_at = existing.created_at;
+        policy_set.updated_at = chrono::Utc::now();
+        self.save_policy_set(&policy_set).await?;
+
+        info!("Updated policy set: {}", policy_set.id);
+        Ok(policy_set)
+    }
+
+    /// 删除策略集（仍被启动配置引用时不能删除）
+    pub async fn delete_policy_set(&self, policy_set_id: &str) -> Result<(), String> {
+        self.get_policy_set(policy_set_id).await?;
+
+        let users: Vec<String> = self
+            .parameter_manager
+            .get_all_configs()
+            .await?
+            .into_iter()
+            .filter(|config| config.policy_set_ids.iter().any(|id| id == policy_set_id))
+            .map(|config| config.name)
+            .collect();
+        if !users.is_empty() {
+            return Err(format!(
+                "Policy set is used by launch configurations: {}",
+                users.join(", ")
+            ));
+        }
+
+        self.database
+            .delete_policy_set(policy_set_id)
+            .await
+            .map_err(|e| format!("Failed to delete policy set: {}", e))?;
+
+        info!("Deleted policy set: {}", policy_set_id);
+        Ok(())
+    }
+
+    /// 根据策略目录校验策略的名称和取值
+    pub fn validate_policies(&self, policies: &BTreeMap<String, serde_json::Value>) -> PolicyValidationReport {
+        let mut issues = Vec::new();
+        for (name, value) in policies {
+            let mut report = |severity, kind, message: String, suggestion: Option<String>| {
+                issues.push(PolicyIssue {
+                    policy: name.clone(),
+                    severity,
+                    kind,
+                    message,
+                    suggestion,
+                });
+            };
+
+            let Some(definition) = self.catalog.find(name) else {
+                report(
+                    ValidationSeverity::Warning,
+                    PolicyIssueKind::UnknownPolicy,
+                    format!("未知的策略 {}", name),
+                    self.closest_policy(name).map(|p| p.name.clone()),
+                );
+                continue;
+            };
+
+            if definition.deprecated {
+                report(
+                    ValidationSeverity::Warning,
+                    PolicyIssueKind::Deprecated,
+                    format!("策略 {} 已废弃", name),
+                    None,
+                );
+            }
+            if let Err(message) = validate_policy_value(definition, value) {
+                report(
+                    ValidationSeverity::Error,
+                    PolicyIssueKind::InvalidType,
+                    message,
+                    None,
+                );
+            } else if !definition.allowed_values.is_empty() && !definition.allowed_values.contains(value) {
+                let allowed: Vec<String> = definition.allowed_values.iter().map(|v| v.to_string()).collect();
+                report(
+                    ValidationSeverity::Error,
+                    PolicyIssueKind::InvalidValue,
+                    format!("{} 的值必须是 {} 之一: {}", name, allowed.join(", "), value),
+                    None,
+                );
+            }
+        }
+
+        let is_valid = !issues.iter().any(|i| i.severity == ValidationSeverity::Error);
+        PolicyValidationReport { issues, is_valid }
+    }
+
+    // 私有方法
+
+    async fn save_policy_set(&self, policy_set: &PolicySet) -> Result<(), String> {
+        if policy_set.name.trim().is_empty() {
+            return Err("Policy set name cannot be empty".to_string());
+        }
+
+        // 未知策略只给出警告，目录不可能覆盖所有策略
+        let report = self.validate_policies(&policy_set.policies);
+        if !report.is_valid {
+            let errors: Vec<String> = report
+                .issues
+                .into_iter()
+                .filter(|i| i.severity == ValidationSeverity::Error)
+                .map(|i| i.message)
+                .collect();
+            return Err(format!("Invalid policies: {}", errors.join("; ")));
+        }
+
+        self.database
+            .save_policy_set(policy_set)
+            .await
+            .map_err(|e| format!("Failed to save policy set: {}", e))
+    }
+
+    /// 查找与给定名称最接近的已知策略（忽略大小写，编辑距离不超过 2）
+    fn closest_policy(&self, name: &str) -> Option<&PolicyDefinition> {
+        let name = name.to_ascii_lowercase();
+        self.catalog
+            .policies
+            .iter()
+            .map(|p| (edit_distance(&p.name.to_ascii_lowercase(), &name), p))
+            .filter(|(distance, _)| *distance <= 2)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, p)| p)
+    }
+}
+
+fn validate_policy_value(definition: &PolicyDefinition, value: &serde_json::Value) -> Result<(), String> {
+    if !definition.value_type.matches(value) {
+        return Err(format!(
+            "{} 的值必须是 {:?} 类型: {}",
+            definition.name, definition.value_type, value
+        ));
+    }
+    if definition.value_type == PolicyValueType::List {
+        let item_type = definition.item_type.unwrap_or(PolicyValueType::String);
+        if let Some(item) = value.as_array().into_iter().flatten().find(|item| !item_type.matches(item)) {
+            return Err(format!(
+                "{} 的列表项必须是 {:?} 类型: {}",
+                definition.name, item_type, item
+            ));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,424 @@
+use crate::models::{normalize_switch, SwitchCatalog};
+use crate::services::flag_validator::split_flag;
+use crate::utils::get_app_data_dir;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tracing::{error, info};
+
+/// 组织策略文件路径的环境变量（未设置时使用应用数据目录下的 security-policy.json）
+const POLICY_PATH_ENV: &str = "CHROME_TESTER_SECURITY_POLICY";
+const POLICY_FILE_NAME: &str = "security-policy.json";
+
+/// 安全问题的严重程度
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SecuritySeverity {
+    Info,
+    Warning,
+    High,
+    Critical,
+}
+
+/// 单条安全检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityFinding {
+    pub rule_id: String,
+    pub flag: String,
+    pub severity: SecuritySeverity,
+    pub message: String,
+    pub rationale: String,
+    pub fix: Option<String>,
+    pub forbidden_by_policy: bool,
+}
+
+/// 安全性验证结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityValidation {
+    pub is_safe: bool,
+    pub dangerous_parameter_count: usize,
+    pub warnings: Vec<String>,
+    pub findings: Vec<SecurityFinding>,
+    pub highest_severity: Option<SecuritySeverity>,
+    pub blocked: bool,                  // 策略禁止启动
+    pub requires_acknowledgement: bool, // 启动前需要用户确认风险
+}
+
+/// 组织安全策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityPolicy {
+    #[serde(default)]
+    pub forbidden_flags: Vec<String>, // 禁止使用的开关，支持以 "*" 结尾的前缀匹配
+    #[serde(default)]
+    pub disabled_rules: Vec<String>,
+    #[serde(default)]
+    pub severity_overrides: HashMap<String, SecuritySeverity>, // 按规则ID调整严重程度
+    #[serde(default = "default_acknowledge_severity")]
+    pub acknowledge_severity: SecuritySeverity, // 达到该级别需要确认后才能启动
+    #[serde(default)]
+    pub block_severity: Option<SecuritySeverity>, // 达到该级别直接禁止启动
+}
+
+fn default_acknowledge_severity() -> SecuritySeverity {
+    SecuritySeverity::Critical
+}
+
+impl Default for SecurityPolicy {
+    fn default() -> Self {
+        Self {
+            forbidden_flags: Vec::new(),
+            disabled_rules: Vec::new(),
+            severity_overrides: HashMap::new(),
+            acknowledge_severity: default_acknowledge_severity(),
+            block_severity: None,
+        }
+    }
+}
+
+impl SecurityPolicy {
+    /// 策略文件无法读取时使用的严格策略
+    fn strict() -> Self {
+        Self {
+            acknowledge_severity: SecuritySeverity::Warning,
+            block_severity: Some(SecuritySeverity::High),
+            ..Self::default()
+        }
+    }
+
+    fn forbids(&self, switch: &str) -> bool {
+        self.forbidden_flags.iter().any(|pattern| {
+            let pattern = normalize_switch(pattern.trim());
+            let pattern = pattern.as_str();
+            match pattern.strip_suffix('*') {
+                Some(prefix) => switch.starts_with(prefix),
+                None => switch == pattern.split('=').next().unwrap_or(pattern),
+            }
+        })
+    }
+}
+
+/// 解析后的命令行开关（单横线写法统一为 "--name"）
+struct ArgumentSet {
+    switches: HashMap<String, Option<String>>,
+}
+
+impl ArgumentSet {
+    fn new(args: &[String]) -> Self {
+        Self {
+            switches: args
+                .iter()
+                .map(|arg| normalize_switch(arg.trim()))
+                .filter(|arg| arg.starts_with("--"))
+                .map(|arg| {
+                    let (switch, value) = split_flag(&arg);
+                    (switch.to_string(), value.map(str::to_string))
+                })
+                .collect(),
+        }
+    }
+
+    fn has(&self, switch: &str) -> bool {
+        self.switches.contains_key(switch)
+    }
+
+    fn value(&self, switch: &str) -> Option<&str> {
+        self.switches.get(switch).and_then(|v| v.as_deref())
+    }
+
+    fn list_contains(&self, switch: &str, item: &str) -> bool {
+        self.value(switch)
+            .map(|v| v.split(',').any(|i| i.trim() == item))
+            .unwrap_or(false)
+    }
+}
+
+/// 内置安全规则
+struct SecurityRule {
+    id: &'static str,
+    flag: &'static str,
+    severity: SecuritySeverity,
+    applies: fn(&ArgumentSet) -> bool,
+    message: &'static str,
+    rationale: &'static str,
+    fix: Option<&'static str>,
+}
+
+const RULES: &[SecurityRule] = &[
+    SecurityRule {
+        id: "web-security-default-profile",
+        flag: "--disable-web-security",
+        severity: SecuritySeverity::Critical,
+        applies: |args| !args.has("--user-data-dir"),
+        message: "在默认用户数据目录中禁用了同源策略",
+        rationale: "禁用同源策略后任何网页都能读取已登录站点的数据，默认配置文件中保存着真实的 Cookie 和凭据",
+        fix: Some("添加 --user-data-dir 指向专用的临时目录"),
+    },
+    SecurityRule {
+        id: "web-security",
+        flag: "--disable-web-security",
+        severity: SecuritySeverity::High,
+        applies: |args| args.has("--user-data-dir"),
+        message: "禁用了同源策略",
+        rationale: "浏览器中打开的任何网页都可以跨域读取其他站点的数据",
+        fix: Some("仅在访问受信任的测试站点时使用"),
+    },
+    SecurityRule {
+        id: "no-sandbox-headed",
+        flag: "--no-sandbox",
+        severity: SecuritySeverity::Critical,
+        applies: |args| !args.has("--headless"),
+        message: "在有界面的浏览器中禁用了沙箱",
+        rationale: "沙箱是渲染进程被攻破后的最后一道防线，有界面的浏览器通常会访问不受信任的网页",
+        fix: Some("移除 --no-sandbox，或仅在容器中以 --headless 模式使用"),
+    },
+    SecurityRule {
+        id: "no-sandbox-headless",
+        flag: "--no-sandbox",
+        severity: SecuritySeverity::Warning,
+        applies: |args| args.has("--headless"),
+        message: "在无头模式下禁用了沙箱",
+        rationale: "无头浏览器被攻破后同样可以访问本机文件和网络",
+        fix: Some("仅在隔离的容器或虚拟机中使用"),
+    },
+    SecurityRule {
+        id: "ignore-certificate-errors",
+        flag: "--ignore-certificate-errors",
+        severity: SecuritySeverity::High,
+        applies: |_| true,
+        message: "忽略所有证书错误",
+        rationale: "中间人可以无提示地篡改或窃听所有 HTTPS 流量",
+        fix: Some("改用 --ignore-certificate-errors-spki-list 只信任指定证书，或安装测试用 CA"),
+    },
+    SecurityRule {
+        id: "insecure-content",
+        flag: "--allow-running-insecure-content",
+        severity: SecuritySeverity::Warning,
+        applies: |_| true,
+        message: "允许 HTTPS 页面加载 HTTP 资源",
+        rationale: "明文加载的脚本可能被篡改，从而控制 HTTPS 页面",
+        fix: None,
+    },
+    SecurityRule {
+        id: "file-access-from-files",
+        flag: "--allow-file-access-from-files",
+        severity: SecuritySeverity::High,
+        applies: |_| true,
+        message: "允许 file:// 页面读取其他本地文件",
+        rationale: "打开下载的 HTML 文件即可读取并上传本机任意文件",
+        fix: Some("使用本地 HTTP 服务器提供测试页面"),
+    },
+    SecurityRule {
+        id: "site-isolation-disabled",
+        flag: "--disable-site-isolation-trials",
+        severity: SecuritySeverity::High,
+        applies: |_| true,
+        message: "禁用了站点隔离",
+        rationale: "不同站点可能共享渲染进程，使 Spectre 类攻击可以读取跨站数据",
+        fix: None,
+    },
+    SecurityRule {
+        id: "site-isolation-feature-disabled",
+        flag: "--disable-features",
+        severity: SecuritySeverity::High,
+        applies: |args| {
+            args.list_contains("--disable-features", "IsolateOrigins")
+                || args.list_contains("--disable-features", "site-per-process")
+        },
+        message: "通过 --disable-features 禁用了站点隔离",
+        rationale: "不同站点可能共享渲染进程，使 Spectre 类攻击可以读取跨站数据",
+        fix: Some("从 --disable-features 中移除 IsolateOrigins 和 site-per-process"),
+    },
+    SecurityRule {
+        id: "remote-debugging-exposed",
+        flag: "--remote-debugging-address",
+        severity: SecuritySeverity::Critical,
+        applies: |args| {
+            !matches!(
+                args.value("--remote-debugging-address"),
+                Some("127.0.0.1") | Some("localhost") | Some("::1") | None
+            )
+        },
+        message: "远程调试端口监听在非本机地址",
+        rationale: "远程调试协议可以完全控制浏览器，网络中的任何人都能读取页面内容和 Cookie",
+        fix: Some("将 --remote-debugging-address 设为 127.0.0.1，通过 SSH 隧道远程访问"),
+    },
+    SecurityRule {
+        id: "remote-allow-any-origin",
+        flag: "--remote-allow-origins",
+        severity: SecuritySeverity::Warning,
+        applies: |args| args.list_contains("--remote-allow-origins", "*"),
+        message: "允许任意来源连接远程调试端口",
+        rationale: "本机打开的任何网页都可以连接调试端口并控制浏览器",
+        fix: Some("只列出需要连接调试端口的具体来源"),
+    },
+    SecurityRule {
+        id: "remote-debugging-default-profile",
+        flag: "--remote-debugging-port",
+        severity: SecuritySeverity::Warning,
+        applies: |args| !args.has("--user-data-dir") && !args.has("--profile"),
+        message: "在默认用户数据目录上开启了远程调试",
+        rationale: "调试客户端可以读取默认配置文件中的 Cookie 和已保存的密码；Chrome 136 起会忽略该开关",
+        fix: Some("添加 --user-data-dir（Firefox 为 --profile）指向专用目录"),
+    },
+];
+
+/// 基于规则和组织策略的启动参数安全检查
+pub struct SecurityPolicyEngine {
+    policy: std::sync::RwLock<SecurityPolicy>,
+    policy_path: Option<PathBuf>,
+}
+
+impl SecurityPolicyEngine {
+    /// 创建检查器并加载组织策略文件（不存在时使用默认策略）
+    pub fn load() -> Self {
+        let policy_path = std::env::var_os(POLICY_PATH_ENV)
+            .map(PathBuf::from)
+            .or_else(|| get_app_data_dir().ok().map(|dir| dir.join(POLICY_FILE_NAME)));
+
+        let engine = Self {
+            policy: std::sync::RwLock::new(SecurityPolicy::default()),
+            policy_path,
+        };
+        engine.reload();
+        engine
+    }
+
+    /// 重新读取策略文件
+    pub fn reload(&self) -> SecurityPolicy {
+        let policy = match &self.policy_path {
+            Some(path) if path.exists() => match std::fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+            {
+                Ok(policy) => {
+                    info!("Loaded security policy from {}", path.display());
+                    policy
+                }
+                Err(e) => {
+                    // 策略文件损坏时按严格策略处理，避免绕过组织限制
+                    error!("Failed to load security policy {}: {}", path.display(), e);
+                    SecurityPolicy::strict()
+                }
+            },
+            _ => SecurityPolicy::default(),
+        };
+
+        *self.policy.write().unwrap() = policy.clone();
+        policy
+    }
+
+    pub fn policy(&self) -> SecurityPolicy {
+        self.policy.read().unwrap().clone()
+    }
+
+    /// 检查最终的启动参数
+    pub fn evaluate(&self, args: &[String]) -> SecurityValidation {
+        let findings = self.findings(args);
+        self.summarize(findings)
+    }
+
+    /// 按规则和策略生成检查结果
+    pub fn findings(&self, args: &[String]) -> Vec<SecurityFinding> {
+        let policy = self.policy.read().unwrap();
+        let parsed = ArgumentSet::new(args);
+        let mut findings = Vec::new();
+
+        for switch in parsed.switches.keys() {
+            if policy.forbids(switch) {
+                findings.push(SecurityFinding {
+                    rule_id: "policy-forbidden-flag".to_string(),
+                    flag: switch.to_string(),
+                    severity: SecuritySeverity::Critical,
+                    message: format!("组织策略禁止使用 {}", switch),
+                    rationale: "该开关被组织安全策略列为禁止使用".to_string(),
+                    fix: Some(format!("移除 {}", switch)),
+                    forbidden_by_policy: true,
+                });
+            }
+        }
+
+        for rule in RULES {
+            if policy.disabled_rules.iter().any(|id| id == rule.id) {
+                continue;
+            }
+            if parsed.has(rule.flag) && (rule.applies)(&parsed) {
+                findings.push(SecurityFinding {
+                    rule_id: rule.id.to_string(),
+                    flag: rule.flag.to_string(),
+                    severity: policy
+                        .severity_overrides
+                        .get(rule.id)
+                        .copied()
+                        .unwrap_or(rule.severity),
+                    message: rule.message.to_string(),
+                    rationale: rule.rationale.to_string(),
+                    fix: rule.fix.map(str::to_string),
+                    forbidden_by_policy: false,
+                });
+            }
+        }
+
+        // 目录中标记为危险、但没有专门规则的开关
+        let catalog = SwitchCatalog::builtin();
+        for switch in parsed.switches.keys() {
+            if RULES.iter().any(|r| r.flag == *switch) {
+                continue;
+            }
+            if let Some(definition) = catalog.find(switch).filter(|s| s.is_dangerous) {
+                findings.push(Self::dangerous_flag_finding(switch, &definition.description));
+            }
+        }
+
+        findings.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.flag.cmp(&b.flag)));
+        findings
+    }
+
+    /// 用户标记为危险的参数
+    pub fn dangerous_flag_finding(flag: &str, description: &str) -> SecurityFinding {
+        SecurityFinding {
+            rule_id: "dangerous-flag".to_string(),
+            flag: flag.to_string(),
+            severity: SecuritySeverity::Warning,
+            message: format!("危险参数: {} - {}", flag, description),
+            rationale: "该参数会降低浏览器的安全性".to_string(),
+            fix: None,
+            forbidden_by_policy: false,
+        }
+    }
+
+    /// 按策略汇总检查结果
+    pub fn summarize(&self, findings: Vec<SecurityFinding>) -> SecurityValidation {
+        let policy = self.policy.read().unwrap();
+        let highest_severity = findings.iter().map(|f| f.severity).max();
+
+        let blocked = findings.iter().any(|f| f.forbidden_by_policy)
+            || matches!((highest_severity, policy.block_severity), (Some(h), Some(b)) if h >= b);
+        let requires_acknowledgement = !blocked
+            && highest_severity
+                .map(|h| h >= policy.acknowledge_severity)
+                .unwrap_or(false);
+
+        let mut dangerous_flags: Vec<&str> = findings
+            .iter()
+            .filter(|f| f.severity >= SecuritySeverity::Warning)
+            .map(|f| f.flag.as_str())
+            .collect();
+        dangerous_flags.sort();
+        dangerous_flags.dedup();
+
+        SecurityValidation {
+            is_safe: findings.is_empty(),
+            dangerous_parameter_count: dangerous_flags.len(),
+            warnings: findings.iter().map(|f| f.message.clone()).collect(),
+            highest_severity,
+            blocked,
+            requires_acknowledgement,
+            findings,
+        }
+    }
+}
+
+impl SecurityValidation {
+    pub fn has_critical_warnings(&self) -> bool {
+        self.highest_severity == Some(SecuritySeverity::Critical)
+    }
+}
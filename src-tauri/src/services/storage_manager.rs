@@ -0,0 +1,541 @@
+use crate::database::Database;
+use crate::models::{
+    compare_version_strings, BrowserDeleteOptions, BrowserInfo, BrowserStorage, DownloadStatus, OrphanAction,
+    Platform, RepairIssue, RepairIssueKind, RepairOptions, RepairReport, RetentionCandidate,
+    RetentionFailure, RetentionPolicy, RetentionReport, StorageUsage,
+};
+use crate::services::{BrowserManager, DownloadManager};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const RETENTION_POLICY_KEY: &str = "storage.retention_policy";
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+pub struct StorageManager {
+    database: Arc<Database>,
+    browser_manager: Arc<BrowserManager>,
+    download_manager: Arc<DownloadManager>,
+}
+
+impl StorageManager {
+    pub fn new(
+        database: Arc<Database>,
+        browser_manager: Arc<BrowserManager>,
+        download_manager: Arc<DownloadManager>,
+    ) -> Self {
+        Self {
+            database,
+            browser_manager,
+            download_manager,
+        }
+    }
+
+    /// 遍历目录统计实际磁盘占用，并同步数据库中记录的浏览器大小
+    pub async fn get_storage_usage(&self) -> Result<StorageUsage, String> {
+        let browsers = self.measure_browsers().await?;
+        let browsers_bytes = browsers
+            .iter()
+            .filter(|b| !b.is_external)
+            .map(|b| b.size_bytes)
+            .sum();
+
+        let profiles_bytes = dir_size_or_zero(&crate::utils::get_profiles_dir()?).await;
+        let logs_bytes = dir_size_or_zero(&crate::utils::get_launch_logs_dir()?).await;
+        let artifacts_bytes = dir_size_or_zero(&crate::utils::get_artifacts_dir()?).await;
+        let trash_bytes = dir_size_or_zero(&crate::utils::get_trash_dir()?).await;
+
+        Ok(StorageUsage {
+            browsers,
+            browsers_bytes,
+            profiles_bytes,
+            logs_bytes,
+            artifacts_bytes,
+            trash_bytes,
+            total_bytes: browsers_bytes + profiles_bytes + logs_bytes + artifacts_bytes + trash_bytes,
+        })
+    }
+
+    pub async fn get_retention_policy(&self) -> Result<RetentionPolicy, String> {
+        let value = self
+            .database
+            .get_config(RETENTION_POLICY_KEY)
+            .await
+            .map_err(|e| format!("Failed to load retention policy: {}", e))?;
+
+        match value {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Failed to parse retention policy: {}", e)),
+            None => Ok(RetentionPolicy::default()),
+        }
+    }
+
+    pub async fn set_retention_policy(&self, policy: RetentionPolicy) -> Result<(), String> {
+        if policy.keep_latest_per_type == Some(0) {
+            return Err("keep_latest_per_type must be at least 1".to_string());
+        }
+        if matches!(policy.max_total_gb, Some(gb) if gb.is_nan() || gb < 0.0) {
+            return Err("max_total_gb must not be negative".to_string());
+        }
+
+        let value = serde_json::to_string(&policy)
+            .map_err(|e| format!("Failed to serialize retention policy: {}", e))?;
+        self.database
+            .set_config(RETENTION_POLICY_KEY, &value)
+            .await
+            .map_err(|e| format!("Failed to save retention policy: {}", e))
+    }
+
+    /// 按保存的保留策略清理浏览器版本，dry_run 时只列出将被删除的版本
+    pub async fn apply_retention_policy(&self, dry_run: bool) -> Result<RetentionReport, String> {
+        let policy = self.get_retention_policy().await?;
+        let browsers = self.browser_manager.list_browsers().await?;
+        let storage = self.measure_browsers().await?;
+        let sizes: HashMap<String, u64> = storage
+            .into_iter()
+            .map(|s| (s.browser_id, s.size_bytes))
+            .collect();
+
+        // 正在运行的浏览器计入版本数和总占用，但不会被删除
+        let managed: Vec<BrowserInfo> = browsers.into_iter().filter(|b| !b.is_external).collect();
+        let mut running = HashSet::new();
+        for browser in &managed {
+            if self.browser_manager.is_browser_running(&browser.id).await {
+                running.insert(browser.id.clone());
+            }
+        }
+        let skipped_running = managed
+            .iter()
+            .filter(|b| running.contains(&b.id))
+            .map(|b| b.id.clone())
+            .collect();
+
+        let candidates = retention_candidates(&policy, &managed, &sizes, &running);
+        let mut report = RetentionReport {
+            dry_run,
+            policy,
+            candidates,
+            deleted: Vec::new(),
+            failed: Vec::new(),
+            skipped_running,
+            freed_bytes: 0,
+        };
+
+        if dry_run {
+            return Ok(report);
+        }
+
+        for candidate in &report.candidates {
+            let options = BrowserDeleteOptions {
+                stop_running: false,
+                permanent: true,
+            };
+            match self
+                .browser_manager
+                .delete_browser(&candidate.browser_id, options)
+                .await
+            {
+                Ok(()) => {
+                    tracing::info!(
+                        "Retention policy removed browser {} ({})",
+                        candidate.browser_id,
+                        candidate.reasons.join("; ")
+                    );
+                    report.deleted.push(candidate.browser_id.clone());
+                    report.freed_bytes += candidate.size_bytes;
+                }
+                Err(error) => report.failed.push(RetentionFailure {
+                    browser_id: candidate.browser_id.clone(),
+                    error,
+                }),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 核对数据库记录与浏览器目录，按选项修复不一致之处
+    pub async fn scan_and_repair(&self, options: RepairOptions) -> Result<RepairReport, String> {
+        let browsers = self.browser_manager.list_browsers().await?;
+        let mut issues = Vec::new();
+
+        for browser in &browsers {
+            if let Some(issue) = self.check_record(browser, &options).await {
+                issues.push(issue);
+            }
+        }
+
+        // 下载过程中的目录还没有记录，此时不处理孤立目录
+        let downloads_active = self
+            .download_manager
+            .list_download_tasks()
+            .await
+            .iter()
+            .any(|task| {
+                matches!(
+                    task.status,
+                    DownloadStatus::Pending | DownloadStatus::Downloading | DownloadStatus::Retrying
+                )
+            });
+
+        let install_dirs = find_install_dirs(&crate::utils::get_browsers_dir()?).await?;
+        let scanned_directories = install_dirs.len();
+        for dir in install_dirs {
+            let known = browsers.iter().any(|b| {
+                !b.is_external && (b.install_path.starts_with(&dir) || dir.starts_with(&b.install_path))
+            });
+            if !known {
+                issues.push(self.handle_orphan(&dir, &options, downloads_active).await);
+            }
+        }
+
+        let fixed_count = issues.iter().filter(|issue| issue.fixed).count();
+        tracing::info!(
+            "Consistency scan found {} issue(s), fixed {}",
+            issues.len(),
+            fixed_count
+        );
+
+        Ok(RepairReport {
+            dry_run: options.dry_run,
+            scanned_records: browsers.len(),
+            scanned_directories,
+            issues,
+            fixed_count,
+        })
+    }
+
+    async fn check_record(&self, browser: &BrowserInfo, options: &RepairOptions) -> Option<RepairIssue> {
+        let install_missing = !browser.is_external && !browser.install_path.is_dir();
+        let executable_missing = !browser.executable_path.is_file();
+
+        // 外部浏览器只记录可执行文件，文件不存在即视为失效
+        if install_missing || (browser.is_external && executable_missing) {
+            let path = if install_missing {
+                &browser.install_path
+            } else {
+                &browser.executable_path
+            };
+            let mut issue = new_issue(
+                RepairIssueKind::StaleRecord,
+                Some(browser.id.clone()),
+                path,
+                format!("{:?} {} 的文件已不存在", browser.browser_type, browser.version),
+            );
+            if options.remove_stale_records && !options.dry_run {
+                match self.database.delete_browser(&browser.id).await {
+                    Ok(()) => resolve(&mut issue, "已删除失效记录".to_string()),
+                    Err(e) => issue.error = Some(format!("Failed to delete browser record: {}", e)),
+                }
+            }
+            return Some(issue);
+        }
+
+        if !executable_missing {
+            return None;
+        }
+
+        let located = locate_executable(browser).await;
+        let mut issue = new_issue(
+            RepairIssueKind::WrongExecutable,
+            Some(browser.id.clone()),
+            &browser.executable_path,
+            match &located {
+                Some(path) => format!("可执行文件不存在，找到 {}", path.display()),
+                None => "可执行文件不存在，安装目录中也未找到".to_string(),
+            },
+        );
+        if options.fix_executables && !options.dry_run {
+            match located {
+                Some(path) => {
+                    let mut updated = browser.clone();
+                    updated.executable_path = path.clone();
+                    match self.database.save_browser(&updated).await {
+                        Ok(()) => resolve(
+                            &mut issue,
+                            format!("可执行文件路径已更新为 {}", path.display()),
+                        ),
+                        Err(e) => {
+                            issue.error = Some(format!("Failed to update browser record: {}", e))
+                        }
+                    }
+                }
+                None => issue.error = Some("No browser executable found in install directory".to_string()),
+            }
+        }
+        Some(issue)
+    }
+
+    async fn handle_orphan(&self, dir: &Path, options: &RepairOptions, downloads_active: bool) -> RepairIssue {
+        let mut issue = new_issue(
+            RepairIssueKind::OrphanDirectory,
+            None,
+            dir,
+            "目录没有对应的浏览器记录".to_string(),
+        );
+        if options.dry_run || options.orphan_action == OrphanAction::Report {
+            return issue;
+        }
+        if downloads_active {
+            issue.error = Some("Downloads in progress, orphan directories were left untouched".to_string());
+            return issue;
+        }
+
+        match options.orphan_action {
+            OrphanAction::Register => match self.register_orphan(dir).await {
+                Ok(browser) => {
+                    issue.browser_id = Some(browser.id.clone());
+                    resolve(
+                        &mut issue,
+                        format!("已注册为 {:?} {}", browser.browser_type, browser.version),
+                    );
+                }
+                Err(e) => issue.error = Some(e),
+            },
+            OrphanAction::Delete => match tokio::fs::remove_dir_all(dir).await {
+                Ok(()) => resolve(&mut issue, "已删除目录".to_string()),
+                Err(e) => issue.error = Some(format!("Failed to delete directory: {}", e)),
+            },
+            OrphanAction::Report => {}
+        }
+        issue
+    }
+
+    /// 识别孤立目录中的浏览器并补充记录
+    async fn register_orphan(&self, dir: &Path) -> Result<BrowserInfo, String> {
+        let detected = crate::utils::detect_browser_in_dir(dir).await?;
+
+        // 同一构建已有记录时不能再注册，否则会违反构建唯一索引
+        if let Some(existing) = self
+            .browser_manager
+            .find_installed_build(&detected.browser_type, &detected.version, detected.platform)
+            .await?
+        {
+            return Err(format!(
+                "{:?} {} ({}) is already registered at {}",
+                detected.browser_type,
+                detected.version,
+                detected.platform,
+                existing.install_path.to_string_lossy()
+            ));
+        }
+
+        let mut executable_path = DownloadManager::find_executable(dir, &detected.browser_type, detected.platform);
+        if !executable_path.is_file() {
+            executable_path = detected.executable_path;
+        }
+
+        let browser = BrowserInfo {
+            id: uuid::Uuid::new_v4().to_string(),
+            browser_type: detected.browser_type,
+            version: detected.version,
+            platform: detected.platform,
+            install_path: dir.to_path_buf(),
+            executable_path,
+            download_date: chrono::Utc::now(),
+            file_size: dir_size_or_zero(dir).await,
+            is_running: false,
+            is_external: false,
+            last_launched: None,
+        };
+
+        self.database
+            .save_browser(&browser)
+            .await
+            .map_err(|e| format!("Failed to save browser to database: {}", e))?;
+
+        Ok(browser)
+    }
+
+    async fn measure_browsers(&self) -> Result<Vec<BrowserStorage>, String> {
+        let browsers = self.browser_manager.list_browsers().await?;
+        let mut result = Vec::with_capacity(browsers.len());
+
+        for browser in browsers {
+            let size_bytes = dir_size_or_zero(&browser.install_path).await;
+            if !browser.is_external && size_bytes != browser.file_size {
+                if let Err(e) = self.database.set_browser_file_size(&browser.id, size_bytes).await {
+                    tracing::warn!("Failed to update size of browser {}: {}", browser.id, e);
+                }
+            }
+
+            result.push(BrowserStorage {
+                browser_id: browser.id,
+                browser_type: browser.browser_type,
+                version: browser.version,
+                install_path: browser.install_path.to_string_lossy().to_string(),
+                size_bytes,
+                is_external: browser.is_external,
+                last_launched: browser.last_launched,
+            });
+        }
+
+        Ok(result)
+    }
+}
+
+/// 依次应用各条保留规则，返回需要删除的浏览器（按最久未使用排序，不含正在运行的浏览器）
+fn retention_candidates(
+    policy: &RetentionPolicy,
+    browsers: &[BrowserInfo],
+    sizes: &HashMap<String, u64>,
+    running: &HashSet<String>,
+) -> Vec<RetentionCandidate> {
+    let now = chrono::Utc::now();
+    let last_used = |b: &BrowserInfo| b.last_launched.unwrap_or(b.download_date);
+    let size = |b: &BrowserInfo| sizes.get(&b.id).copied().unwrap_or(b.file_size);
+    let mut reasons: HashMap<&str, Vec<String>> = HashMap::new();
+
+    // 每种类型在每个平台上只保留最新的若干个版本
+    if let Some(keep) = policy.keep_latest_per_type {
+        let mut by_build: HashMap<(&str, Platform), Vec<&BrowserInfo>> = HashMap::new();
+        for browser in browsers {
+            by_build
+                .entry((browser.browser_type.as_str(), browser.platform))
+                .or_default()
+                .push(browser);
+        }
+        for group in by_build.values_mut() {
+            group.sort_by(|a, b| {
+                compare_version_strings(&b.version, &a.version)
+                    .then(b.download_date.cmp(&a.download_date))
+            });
+            for browser in group.iter().skip(keep).filter(|b| !running.contains(&b.id)) {
+                reasons
+                    .entry(&browser.id)
+                    .or_default()
+                    .push(format!("超出每种类型和平台保留的最新 {} 个版本", keep));
+            }
+        }
+    }
+
+    // 长期未使用
+    if let Some(days) = policy.max_unused_days {
+        for browser in browsers.iter().filter(|b| !running.contains(&b.id)) {
+            let unused_days = (now - last_used(browser)).num_days();
+            if unused_days > days as i64 {
+                reasons
+                    .entry(&browser.id)
+                    .or_default()
+                    .push(format!("已 {} 天未使用", unused_days));
+            }
+        }
+    }
+
+    // 剩余版本（含正在运行的版本）超出总空间上限时，从最久未使用的开始删除
+    if let Some(max_gb) = policy.max_total_gb {
+        let limit = (max_gb * BYTES_PER_GB) as u64;
+        let mut remaining: Vec<&BrowserInfo> = browsers
+            .iter()
+            .filter(|b| !reasons.contains_key(b.id.as_str()))
+            .collect();
+        remaining.sort_by_key(|b| last_used(b));
+
+        let mut total: u64 = remaining.iter().map(|b| size(b)).sum();
+        for browser in remaining.into_iter().filter(|b| !running.contains(&b.id)) {
+            if total <= limit {
+                break;
+            }
+            total -= size(browser);
+            reasons
+                .entry(&browser.id)
+                .or_default()
+                .push(format!("浏览器总占用超出 {} GB 上限", max_gb));
+        }
+    }
+
+    let mut candidates: Vec<RetentionCandidate> = browsers
+        .iter()
+        .filter_map(|browser| {
+            reasons.remove(browser.id.as_str()).map(|reasons| RetentionCandidate {
+                browser_id: browser.id.clone(),
+                browser_type: browser.browser_type.clone(),
+                version: browser.version.clone(),
+                size_bytes: size(browser),
+                last_used: last_used(browser),
+                reasons,
+            })
+        })
+        .collect();
+    candidates.sort_by_key(|c| c.last_used);
+    candidates
+}
+
+fn new_issue(kind: RepairIssueKind, browser_id: Option<String>, path: &Path, detail: String) -> RepairIssue {
+    RepairIssue {
+        kind,
+        browser_id,
+        path: path.to_string_lossy().to_string(),
+        detail,
+        fixed: false,
+        action: None,
+        error: None,
+    }
+}
+
+fn resolve(issue: &mut RepairIssue, action: String) {
+    issue.fixed = true;
+    issue.action = Some(action);
+}
+
+/// 在标准位置或安装目录中重新查找可执行文件
+async fn locate_executable(browser: &BrowserInfo) -> Option<PathBuf> {
+    let candidate = DownloadManager::find_executable(
+        &browser.install_path,
+        &browser.browser_type,
+        browser.platform,
+    );
+    if candidate.is_file() {
+        return Some(candidate);
+    }
+    crate::utils::detect_browser_in_dir(&browser.install_path)
+        .await
+        .ok()
+        .map(|detected| detected.executable_path)
+}
+
+/// 浏览器目录下的安装目录（browsers/<类型>/<平台>-<版本>），跳过导入用的临时目录
+async fn find_install_dirs(root: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut dirs = Vec::new();
+    if !root.is_dir() {
+        return Ok(dirs);
+    }
+
+    for type_dir in list_subdirs(root).await? {
+        dirs.extend(list_subdirs(&type_dir).await?);
+    }
+    Ok(dirs)
+}
+
+async fn list_subdirs(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut entries = tokio::fs::read_dir(dir)
+        .await
+        .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+
+    let mut dirs = Vec::new();
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?
+    {
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !hidden && entry.path().is_dir() {
+            dirs.push(entry.path());
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+async fn dir_size_or_zero(path: &Path) -> u64 {
+    if !path.exists() {
+        return 0;
+    }
+    match crate::utils::get_dir_size(path).await {
+        Ok(size) => size,
+        Err(e) => {
+            tracing::warn!("Failed to measure size of {}: {}", path.display(), e);
+            0
+        }
+    }
+}
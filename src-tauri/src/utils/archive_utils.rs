@@ -0,0 +1,207 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// 支持的归档格式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveFormat {
+    Zip,
+    TarXz,
+}
+
+impl ArchiveFormat {
+    /// 根据文件名判断归档格式
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let name = path
+            .as_ref()
+            .file_name()?
+            .to_string_lossy()
+            .to_lowercase();
+
+        if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else if name.ends_with(".tar.xz") || name.ends_with(".txz") {
+            Some(ArchiveFormat::TarXz)
+        } else {
+            None
+        }
+    }
+}
+
+/// 解压归档文件到目标目录
+pub async fn extract_archive<P: AsRef<Path>, Q: AsRef<Path>>(
+    archive_path: P,
+    dest_dir: Q,
+) -> Result<(), String> {
+    let archive_path = archive_path.as_ref().to_path_buf();
+    let dest_dir = dest_dir.as_ref().to_path_buf();
+
+    let format = ArchiveFormat::from_path(&archive_path).ok_or_else(|| {
+        format!(
+            "Unsupported archive format: {}",
+            archive_path.to_string_lossy()
+        )
+    })?;
+
+    // 解压是阻塞操作，放到阻塞线程池中执行
+    tokio::task::spawn_blocking(move || match format {
+        ArchiveFormat::Zip => extract_zip(&archive_path, &dest_dir),
+        ArchiveFormat::TarXz => extract_tar_xz(&archive_path, &dest_dir),
+    })
+    .await
+    .map_err(|e| format!("Archive extraction task failed: {}", e))?
+}
+
+fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<(), String> {
+    let file = File::open(archive_path)
+        .map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read zip archive: {}", e))?;
+
+    archive
+        .extract(dest_dir)
+        .map_err(|e| format!("Failed to extract zip archive: {}", e))
+}
+
+fn extract_tar_xz(archive_path: &Path, dest_dir: &Path) -> Result<(), String> {
+    let file = File::open(archive_path)
+        .map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut archive = tar::Archive::new(xz2::read::XzDecoder::new(file));
+    archive.set_preserve_permissions(true);
+
+    archive
+        .unpack(dest_dir)
+        .map_err(|e| format!("Failed to extract tar.xz archive: {}", e))
+}
+
+/// 待写入 tar.xz 归档的条目
+pub enum ArchiveEntry {
+    /// 内存中的文件内容
+    Data { name: String, content: Vec<u8> },
+    /// 整个目录（保留符号链接）
+    Dir { name: String, source: PathBuf },
+}
+
+/// 创建 tar.xz 归档
+pub async fn create_tar_xz<P: AsRef<Path>>(
+    archive_path: P,
+    entries: Vec<ArchiveEntry>,
+) -> Result<(), String> {
+    let archive_path = archive_path.as_ref().to_path_buf();
+
+    tokio::task::spawn_blocking(move || {
+        let file = File::create(&archive_path)
+            .map_err(|e| format!("Failed to create archive: {}", e))?;
+        let mut builder = tar::Builder::new(xz2::write::XzEncoder::new(file, 6));
+        builder.follow_symlinks(false);
+
+        for entry in entries {
+            match entry {
+                ArchiveEntry::Data { name, content } => {
+                    let mut header = tar::Header::new_gnu();
+                    header.set_size(content.len() as u64);
+                    header.set_mode(0o644);
+                    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+                    header.set_cksum();
+                    builder
+                        .append_data(&mut header, &name, content.as_slice())
+                        .map_err(|e| format!("Failed to write {} to archive: {}", name, e))?;
+                }
+                ArchiveEntry::Dir { name, source } => {
+                    builder
+                        .append_dir_all(&name, &source)
+                        .map_err(|e| format!("Failed to write {} to archive: {}", name, e))?;
+                }
+            }
+        }
+
+        builder
+            .into_inner()
+            .and_then(|encoder| encoder.finish())
+            .map_err(|e| format!("Failed to finish archive: {}", e))?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Archive creation task failed: {}", e))?
+}
+
+/// 移动目录，跨文件系统时回退为复制后删除
+pub async fn move_dir<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<(), String> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    if let Some(parent) = to.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    if tokio::fs::rename(from, to).await.is_ok() {
+        return Ok(());
+    }
+
+    if let Err(e) = copy_dir_recursive(from, to).await {
+        let _ = tokio::fs::remove_dir_all(to).await;
+        return Err(e);
+    }
+    tokio::fs::remove_dir_all(from)
+        .await
+        .map_err(|e| format!("Failed to remove {}: {}", from.display(), e))
+}
+
+/// 递归复制目录（保留符号链接）
+pub async fn copy_dir_recursive<P: AsRef<Path>, Q: AsRef<Path>>(
+    from: P,
+    to: Q,
+) -> Result<(), String> {
+    let from = from.as_ref().to_path_buf();
+    let to = to.as_ref().to_path_buf();
+
+    tokio::task::spawn_blocking(move || copy_dir_blocking(&from, &to))
+        .await
+        .map_err(|e| format!("Directory copy task failed: {}", e))?
+}
+
+fn copy_dir_blocking(from: &Path, to: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(to).map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    let entries = std::fs::read_dir(from)
+        .map_err(|e| format!("Failed to read directory {}: {}", from.display(), e))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+        let source: PathBuf = entry.path();
+        let target = to.join(entry.file_name());
+        let file_type = entry
+            .file_type()
+            .map_err(|e| format!("Failed to read file type: {}", e))?;
+
+        if file_type.is_symlink() {
+            copy_symlink(&source, &target)?;
+        } else if file_type.is_dir() {
+            copy_dir_blocking(&source, &target)?;
+        } else {
+            std::fs::copy(&source, &target)
+                .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path) -> Result<(), String> {
+    let link = std::fs::read_link(source)
+        .map_err(|e| format!("Failed to read symlink {}: {}", source.display(), e))?;
+    std::os::unix::fs::symlink(link, target)
+        .map_err(|e| format!("Failed to create symlink {}: {}", target.display(), e))
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, target: &Path) -> Result<(), String> {
+    // 非 Unix 平台直接复制链接指向的内容
+    if source.is_dir() {
+        copy_dir_blocking(source, target)
+    } else {
+        std::fs::copy(source, target)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))
+    }
+}
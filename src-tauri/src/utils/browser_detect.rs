@@ -0,0 +1,523 @@
+use crate::models::{BrowserType, DiscoverySource, Platform};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::process::Command;
+
+/// 从目录内容中识别出的浏览器信息
+#[derive(Debug, Clone)]
+pub struct DetectedBrowser {
+    pub browser_type: BrowserType,
+    pub version: String,
+    pub platform: Platform,
+    pub executable_path: PathBuf,
+}
+
+/// 搜索可执行文件时的最大目录深度
+const MAX_SEARCH_DEPTH: usize = 4;
+
+/// 识别目录中的浏览器类型、版本和平台
+pub async fn detect_browser_in_dir<P: AsRef<Path>>(root: P) -> Result<DetectedBrowser, String> {
+    let root = root.as_ref().to_path_buf();
+
+    let search_root = root.clone();
+    let (executable_path, browser_type) =
+        tokio::task::spawn_blocking(move || find_browser_executable(&search_root, 0))
+            .await
+            .map_err(|e| format!("Browser detection task failed: {}", e))?
+            .ok_or_else(|| {
+                format!("No browser executable found in {}", root.to_string_lossy())
+            })?;
+
+    let platform = detect_platform_from_binary(&executable_path)
+        .or_else(|| detect_platform_from_path(&executable_path))
+        .ok_or("Unable to determine browser platform")?;
+
+    let version = match detect_version_from_files(&executable_path) {
+        Some(version) => version,
+        None if platform.is_runnable() => {
+            let output = run_version_command(&executable_path)
+                .await
+                .ok_or("Unable to determine browser version")?;
+            parse_version_output(&output).ok_or("Unable to determine browser version")?
+        }
+        None => {
+            return Err(format!(
+                "Unable to determine browser version for foreign platform {}",
+                platform
+            ))
+        }
+    };
+
+    tracing::info!(
+        "Detected {:?} {} ({}) at {:?}",
+        browser_type,
+        version,
+        platform,
+        executable_path
+    );
+
+    Ok(DetectedBrowser {
+        browser_type,
+        version,
+        platform,
+        executable_path,
+    })
+}
+
+/// 在目录树中查找浏览器可执行文件
+fn find_browser_executable(dir: &Path, depth: usize) -> Option<(PathBuf, BrowserType)> {
+    if depth > MAX_SEARCH_DEPTH {
+        return None;
+    }
+
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    entries.sort();
+
+    // 先检查当前层级，再深入子目录
+    for path in &entries {
+        if let Some(found) = classify_executable(path) {
+            return Some(found);
+        }
+    }
+
+    entries
+        .iter()
+        .filter(|path| path.is_dir() && !is_app_bundle(path))
+        .find_map(|path| find_browser_executable(path, depth + 1))
+}
+
+fn is_app_bundle(path: &Path) -> bool {
+    path.extension().map(|ext| ext == "app").unwrap_or(false)
+}
+
+/// 判断路径是否为已知的浏览器可执行文件
+fn classify_executable(path: &Path) -> Option<(PathBuf, BrowserType)> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+
+    // macOS 应用包，可执行文件位于 Contents/MacOS 下
+    if is_app_bundle(path) {
+        let stem = name.trim_end_matches(".app");
+        let browser_type = if stem.starts_with("Chromium") {
+            BrowserType::Chromium
+        } else if stem.starts_with("Google Chrome") {
+            BrowserType::Chrome
+        } else if stem.starts_with("Firefox Nightly") {
+            BrowserType::FirefoxNightly
+        } else if stem.starts_with("Firefox Developer Edition") {
+            BrowserType::FirefoxDevEdition
+        } else if stem.starts_with("Firefox") {
+            BrowserType::Firefox
+        } else {
+            return None;
+        };
+
+        let macos_dir = path.join("Contents").join("MacOS");
+        let executable = [stem.to_string(), "firefox".to_string()]
+            .iter()
+            .map(|exe| macos_dir.join(exe))
+            .find(|exe| exe.is_file())?;
+        let browser_type = match browser_type {
+            BrowserType::Firefox => detect_firefox_channel(&executable).unwrap_or(browser_type),
+            _ => browser_type,
+        };
+        return Some((executable, browser_type));
+    }
+
+    if !path.is_file() {
+        return None;
+    }
+
+    let browser_type = match name.as_str() {
+        "chromedriver" | "chromedriver.exe" => BrowserType::ChromeDriver,
+        "chrome-headless-shell" | "chrome-headless-shell.exe" => BrowserType::ChromeHeadlessShell,
+        "firefox" | "firefox.exe" => detect_firefox_channel(path).unwrap_or(BrowserType::Firefox),
+        "chromium" | "chromium-browser" => BrowserType::Chromium,
+        "google-chrome" => BrowserType::Chrome,
+        "chrome" | "chrome.exe" => {
+            // Chromium 快照使用 chrome-linux / chrome-win 目录，Chrome for Testing 带架构后缀
+            let parent = path
+                .parent()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if matches!(parent.as_str(), "chrome-linux" | "chrome-win" | "chrome-mac") {
+                BrowserType::Chromium
+            } else {
+                BrowserType::Chrome
+            }
+        }
+        _ => return None,
+    };
+
+    Some((path.to_path_buf(), browser_type))
+}
+
+/// 根据可执行文件头识别目标平台
+pub fn detect_platform_from_binary<P: AsRef<Path>>(path: P) -> Option<Platform> {
+    use std::io::Read;
+
+    let mut header = vec![0u8; 4096];
+    let mut file = std::fs::File::open(path).ok()?;
+    let len = file.read(&mut header).ok()?;
+    header.truncate(len);
+
+    let read_u16_le = |offset: usize| -> Option<u16> {
+        header
+            .get(offset..offset + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+    };
+    let read_u32_le = |offset: usize| -> Option<u32> {
+        header
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+
+    if header.starts_with(b"\x7fELF") {
+        // ELF: e_machine 位于偏移 18
+        return match read_u16_le(18)? {
+            0x3E => Some(Platform::Linux64),
+            0xB7 => Some(Platform::LinuxArm64),
+            _ => None,
+        };
+    }
+
+    if header.starts_with(b"MZ") {
+        // PE: e_lfanew 指向 "PE\0\0" 签名，其后为 Machine 字段
+        let pe_offset = read_u32_le(0x3C)? as usize;
+        if header.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+            return None;
+        }
+        return match read_u16_le(pe_offset + 4)? {
+            0x8664 => Some(Platform::Win64),
+            0x014C => Some(Platform::Win32),
+            _ => None,
+        };
+    }
+
+    if header.starts_with(&[0xCF, 0xFA, 0xED, 0xFE]) {
+        // 64 位 Mach-O: cputype 位于偏移 4
+        return match read_u32_le(4)? {
+            0x0100_0007 => Some(Platform::MacX64),
+            0x0100_000C => Some(Platform::MacArm64),
+            _ => None,
+        };
+    }
+
+    None
+}
+
+/// 根据目录命名约定识别平台（如 chrome-linux64、chrome-mac-arm64）
+pub fn detect_platform_from_path<P: AsRef<Path>>(path: P) -> Option<Platform> {
+    for component in path.as_ref().components().rev() {
+        let name = component.as_os_str().to_string_lossy().to_lowercase();
+        if name.ends_with("-linux64") || name == "chrome-linux" {
+            return Some(Platform::Linux64);
+        } else if name.ends_with("-linux-arm64") {
+            return Some(Platform::LinuxArm64);
+        } else if name.ends_with("-mac-arm64") {
+            return Some(Platform::MacArm64);
+        } else if name.ends_with("-mac-x64") || name == "chrome-mac" {
+            return Some(Platform::MacX64);
+        } else if name.ends_with("-win64") {
+            return Some(Platform::Win64);
+        } else if name.ends_with("-win32") || name == "chrome-win" {
+            return Some(Platform::Win32);
+        }
+    }
+
+    let path = path.as_ref();
+    if path.extension().map(|ext| ext == "exe").unwrap_or(false) {
+        Some(Platform::Win64)
+    } else if path.to_string_lossy().contains(".app/Contents/MacOS/") {
+        Some(if cfg!(target_arch = "aarch64") { Platform::MacArm64 } else { Platform::MacX64 })
+    } else {
+        None
+    }
+}
+
+/// 从随附文件（manifest、Info.plist、application.ini）中读取版本号
+pub fn detect_version_from_files<P: AsRef<Path>>(executable_path: P) -> Option<String> {
+    let executable_path = executable_path.as_ref();
+    let exe_dir = executable_path.parent()?;
+
+    // Firefox: application.ini（macOS 下位于 Contents/Resources）
+    let ini_candidates = [
+        exe_dir.join("application.ini"),
+        exe_dir.join("../Resources/application.ini"),
+    ];
+    for ini in &ini_candidates {
+        if let Ok(content) = std::fs::read_to_string(ini) {
+            if let Some(version) = content
+                .lines()
+                .find_map(|line| line.strip_prefix("Version="))
+            {
+                return Some(version.trim().to_string());
+            }
+        }
+    }
+
+    // macOS 应用包: Info.plist
+    if let Ok(content) = std::fs::read_to_string(exe_dir.join("../Info.plist")) {
+        let re = regex::Regex::new(
+            r"<key>CFBundleShortVersionString</key>\s*<string>([^<]+)</string>",
+        )
+        .ok()?;
+        if let Some(captures) = re.captures(&content) {
+            return Some(captures[1].trim().to_string());
+        }
+    }
+
+    // Windows: chrome.exe 旁的 <version>.manifest 文件或 <version> 目录
+    let version_re = regex::Regex::new(r"^(\d+\.\d+\.\d+\.\d+)(\.manifest)?$").ok()?;
+    let entries = std::fs::read_dir(exe_dir).ok()?;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(captures) = version_re.captures(&name) {
+            return Some(captures[1].to_string());
+        }
+    }
+
+    None
+}
+
+/// 执行 `<browser> --version` 并返回输出
+pub async fn run_version_command<P: AsRef<Path>>(executable_path: P) -> Option<String> {
+    let output = tokio::time::timeout(
+        Duration::from_secs(10),
+        Command::new(executable_path.as_ref())
+            .arg("--version")
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// 从 `--version` 输出中提取版本号
+/// (如 "Google Chrome for Testing 131.0.6778.85"、"Mozilla Firefox 128.0esr")
+pub fn parse_version_output(output: &str) -> Option<String> {
+    let re = regex::Regex::new(r"(\d+(?:\.\d+){1,3}(?:[ab]\d+|esr)?)").ok()?;
+    re.captures(output).map(|captures| captures[1].to_string())
+}
+
+/// PATH 中常见的浏览器命令名
+const PATH_BROWSER_NAMES: &[&str] = &[
+    "google-chrome",
+    "google-chrome-stable",
+    "google-chrome-beta",
+    "google-chrome-unstable",
+    "chromium",
+    "chromium-browser",
+    "firefox",
+    "firefox-esr",
+    "chromedriver",
+];
+
+/// 获取各平台常见的浏览器安装位置
+fn well_known_browser_paths() -> Vec<(PathBuf, DiscoverySource)> {
+    let mut paths: Vec<(PathBuf, DiscoverySource)> = Vec::new();
+
+    if cfg!(target_os = "linux") {
+        let system_locations = [
+            "/opt/google/chrome/chrome",
+            "/opt/google/chrome-beta/chrome",
+            "/opt/google/chrome-unstable/chrome",
+            "/usr/lib/chromium/chromium",
+            "/usr/lib/chromium-browser/chromium-browser",
+            "/usr/lib64/chromium-browser/chromium-browser",
+            "/usr/lib/firefox/firefox",
+            "/usr/lib64/firefox/firefox",
+            "/usr/lib/firefox-esr/firefox-esr",
+            "/opt/firefox/firefox",
+        ];
+        paths.extend(
+            system_locations
+                .iter()
+                .map(|p| (PathBuf::from(p), DiscoverySource::SystemLocation)),
+        );
+
+        let snap_locations = ["/snap/bin/chromium", "/snap/bin/firefox"];
+        paths.extend(
+            snap_locations
+                .iter()
+                .map(|p| (PathBuf::from(p), DiscoverySource::Snap)),
+        );
+
+        // 系统级和用户级 Flatpak 导出目录
+        let mut flatpak_dirs = vec![PathBuf::from("/var/lib/flatpak/exports/bin")];
+        if let Some(home) = super::get_env_var("HOME") {
+            flatpak_dirs.push(PathBuf::from(home).join(".local/share/flatpak/exports/bin"));
+        }
+        let flatpak_apps = [
+            "com.google.Chrome",
+            "com.google.ChromeDev",
+            "org.chromium.Chromium",
+            "org.mozilla.firefox",
+        ];
+        for dir in &flatpak_dirs {
+            paths.extend(
+                flatpak_apps
+                    .iter()
+                    .map(|app| (dir.join(app), DiscoverySource::Flatpak)),
+            );
+        }
+    } else if cfg!(target_os = "macos") {
+        let app_locations = [
+            "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+            "/Applications/Google Chrome Beta.app/Contents/MacOS/Google Chrome Beta",
+            "/Applications/Google Chrome Canary.app/Contents/MacOS/Google Chrome Canary",
+            "/Applications/Chromium.app/Contents/MacOS/Chromium",
+            "/Applications/Firefox.app/Contents/MacOS/firefox",
+        ];
+        paths.extend(
+            app_locations
+                .iter()
+                .map(|p| (PathBuf::from(p), DiscoverySource::SystemLocation)),
+        );
+    } else if cfg!(target_os = "windows") {
+        let program_dirs = ["ProgramFiles", "ProgramFiles(x86)", "LOCALAPPDATA"]
+            .iter()
+            .filter_map(|var| super::get_env_var(var))
+            .map(PathBuf::from)
+            .collect::<Vec<_>>();
+        for dir in program_dirs {
+            paths.push((
+                dir.join("Google/Chrome/Application/chrome.exe"),
+                DiscoverySource::SystemLocation,
+            ));
+            paths.push((
+                dir.join("Chromium/Application/chrome.exe"),
+                DiscoverySource::SystemLocation,
+            ));
+            paths.push((
+                dir.join("Mozilla Firefox/firefox.exe"),
+                DiscoverySource::SystemLocation,
+            ));
+        }
+    }
+
+    paths
+}
+
+/// 系统浏览器扫描候选项
+#[derive(Debug, Clone)]
+pub struct SystemBrowserCandidate {
+    pub executable_path: PathBuf,
+    pub source: DiscoverySource,
+}
+
+/// 收集 PATH、常见安装位置以及 Snap/Flatpak 中存在的浏览器可执行文件
+pub async fn find_system_browser_candidates() -> Vec<SystemBrowserCandidate> {
+    let mut candidates = Vec::new();
+
+    for name in PATH_BROWSER_NAMES {
+        if let Some(path) = super::find_in_path(name).await {
+            // PATH 中的 Snap/Flatpak 入口按其实际来源归类
+            let source = classify_install_source(&path).unwrap_or(DiscoverySource::Path);
+            candidates.push(SystemBrowserCandidate {
+                executable_path: path,
+                source,
+            });
+        }
+    }
+
+    for (path, source) in well_known_browser_paths() {
+        if path.is_file() {
+            candidates.push(SystemBrowserCandidate {
+                executable_path: path,
+                source,
+            });
+        }
+    }
+
+    // 同一浏览器可能通过多个符号链接出现，按解析后的路径去重
+    let mut seen = std::collections::HashSet::new();
+    candidates.retain(|candidate| seen.insert(dedup_key(&candidate.executable_path)));
+
+    candidates
+}
+
+fn classify_install_source(path: &Path) -> Option<DiscoverySource> {
+    let path_str = path.to_string_lossy();
+    if path_str.starts_with("/snap/") {
+        Some(DiscoverySource::Snap)
+    } else if path_str.contains("/flatpak/exports/bin/") {
+        Some(DiscoverySource::Flatpak)
+    } else {
+        None
+    }
+}
+
+fn dedup_key(path: &Path) -> PathBuf {
+    // Snap 入口都链接到 /usr/bin/snap，Flatpak 入口是启动脚本，不能解析链接
+    if classify_install_source(path).is_some() {
+        return path.to_path_buf();
+    }
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// 根据随附的 channel-prefs.js 中的更新渠道区分 Firefox 的发布渠道
+pub fn detect_firefox_channel(executable_path: &Path) -> Option<BrowserType> {
+    let exe_dir = executable_path.parent()?;
+    let candidates = [
+        exe_dir.join("defaults/pref/channel-prefs.js"),
+        exe_dir.join("../Resources/defaults/pref/channel-prefs.js"),
+    ];
+    let content = candidates
+        .iter()
+        .find_map(|path| std::fs::read_to_string(path).ok())?;
+
+    // pref("app.update.channel", "esr");
+    let line = content.lines().find(|l| l.contains("\"app.update.channel\""))?;
+    match line.split('"').nth(3)? {
+        "nightly" => Some(BrowserType::FirefoxNightly),
+        "aurora" => Some(BrowserType::FirefoxDevEdition),
+        "esr" => Some(BrowserType::FirefoxEsr),
+        "release" | "beta" => Some(BrowserType::Firefox),
+        _ => None,
+    }
+}
+
+/// 根据可执行文件名和 `--version` 输出推断浏览器类型
+pub fn classify_system_browser(executable_path: &Path, version_output: &str) -> Option<BrowserType> {
+    let name = executable_path
+        .file_name()?
+        .to_string_lossy()
+        .to_lowercase();
+    let output = version_output.to_lowercase();
+
+    if name.contains("chromedriver") || output.starts_with("chromedriver") {
+        Some(BrowserType::ChromeDriver)
+    } else if name.contains("headless-shell") {
+        Some(BrowserType::ChromeHeadlessShell)
+    } else if name.contains("firefox") || output.contains("firefox") {
+        // 渠道文件不可读时根据版本号后缀判断（如 115.15.0esr、133.0a1）
+        detect_firefox_channel(executable_path).or_else(|| {
+            let version = parse_version_output(version_output).unwrap_or_default();
+            Some(if version.ends_with("esr") {
+                BrowserType::FirefoxEsr
+            } else if version.contains('a') {
+                BrowserType::FirefoxNightly
+            } else {
+                BrowserType::Firefox
+            })
+        })
+    } else if name.contains("chromium") || output.starts_with("chromium") {
+        Some(BrowserType::Chromium)
+    } else if name.contains("chrome") || output.starts_with("google chrome") {
+        Some(BrowserType::Chrome)
+    } else {
+        None
+    }
+}
+
@@ -0,0 +1,106 @@
+use crate::models::ProfileSeed;
+use serde_json::Value;
+use std::path::Path;
+
+/// 将初始设置写入 Chrome 用户数据目录的 Default/Preferences 和 Local State
+///
+/// 已有文件中的其他设置保持不变。Chrome 会用 Secure Preferences 中的校验值保护
+/// 主页、启动页等少数首选项，这些首选项无法通过此方式修改。
+pub fn seed_chrome_profile(user_data_dir: &Path, seed: &ProfileSeed) -> Result<(), String> {
+    seed.validate()?;
+
+    if !seed.preferences.is_empty() {
+        let path = user_data_dir.join("Default").join("Preferences");
+        update_json_file(&path, |root| {
+            for (key, value) in &seed.preferences {
+                set_path(root, key, value.clone());
+            }
+        })?;
+    }
+
+    if !seed.local_state.is_empty() || !seed.enabled_experiments.is_empty() {
+        let path = user_data_dir.join("Local State");
+        update_json_file(&path, |root| {
+            for (key, value) in &seed.local_state {
+                set_path(root, key, value.clone());
+            }
+            if !seed.enabled_experiments.is_empty() {
+                enable_experiments(root, &seed.enabled_experiments);
+            }
+        })?;
+    }
+
+    Ok(())
+}
+
+/// 读取 JSON 文件（不存在时视为空对象），修改后写回
+fn update_json_file(path: &Path, update: impl FnOnce(&mut Value)) -> Result<(), String> {
+    let mut root = match std::fs::read_to_string(path) {
+        Ok(content) if !content.trim().is_empty() => serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?,
+        Ok(_) => Value::Object(Default::default()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Value::Object(Default::default()),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    if !root.is_object() {
+        return Err(format!("Unexpected content in {}: expected a JSON object", path.display()));
+    }
+
+    update(&mut root);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {}: {}", parent.display(), e))?;
+    }
+    let content = serde_json::to_string(&root)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+    std::fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// 按以点分隔的路径设置值，途经的非对象节点会被替换为对象
+fn set_path(root: &mut Value, path: &str, value: Value) {
+    if !root.is_object() {
+        *root = Value::Object(Default::default());
+    }
+    let Value::Object(map) = root else {
+        return;
+    };
+    match path.split_once('.') {
+        Some((segment, rest)) => {
+            set_path(map.entry(segment.to_string()).or_insert(Value::Null), rest, value)
+        }
+        None => merge_value(map.entry(path.to_string()).or_insert(Value::Null), value),
+    }
+}
+
+/// 对象逐层合并，其他类型直接替换
+fn merge_value(target: &mut Value, value: Value) {
+    match (target, value) {
+        (Value::Object(target), Value::Object(value)) => {
+            for (key, value) in value {
+                merge_value(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (target, value) => *target = value,
+    }
+}
+
+/// 更新 browser.enabled_labs_experiments，同名实验以新条目为准
+fn enable_experiments(root: &mut Value, experiments: &[String]) {
+    let mut entries: Vec<Value> = root
+        .pointer("/browser/enabled_labs_experiments")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    for experiment in experiments {
+        let name = ProfileSeed::experiment_name(experiment);
+        entries.retain(|entry| {
+            entry
+                .as_str()
+                .map(|e| ProfileSeed::experiment_name(e) != name)
+                .unwrap_or(false)
+        });
+        entries.push(Value::String(experiment.clone()));
+    }
+    set_path(root, "browser.enabled_labs_experiments", Value::Array(entries));
+}
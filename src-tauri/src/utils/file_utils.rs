@@ -1,4 +1,6 @@
+use std::future::Future;
 use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use tokio::fs;
 
 /// 复制文件
@@ -40,19 +42,28 @@ pub async fn get_file_size<P: AsRef<Path>>(path: P) -> Result<u64, std::io::Erro
 
 /// 获取目录大小
 pub async fn get_dir_size<P: AsRef<Path>>(path: P) -> Result<u64, std::io::Error> {
-    let mut total_size = 0;
-    let mut entries = fs::read_dir(path).await?;
+    dir_size(path.as_ref().to_path_buf()).await
+}
 
-    while let Some(entry) = entries.next_entry().await? {
-        let metadata = entry.metadata().await?;
-        if metadata.is_file() {
-            total_size += metadata.len();
-        } else if metadata.is_dir() {
-            total_size += get_dir_size(entry.path()).await?;
+// 递归的 async 调用需要装箱
+fn dir_size(
+    path: PathBuf,
+) -> Pin<Box<dyn Future<Output = Result<u64, std::io::Error>> + Send>> {
+    Box::pin(async move {
+        let mut total_size = 0;
+        let mut entries = fs::read_dir(path).await?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            if metadata.is_file() {
+                total_size += metadata.len();
+            } else if metadata.is_dir() {
+                total_size += dir_size(entry.path()).await?;
+            }
         }
-    }
 
-    Ok(total_size)
+        Ok(total_size)
+    })
 }
 
 /// 检查路径是否存在
@@ -130,4 +141,38 @@ pub async fn calculate_checksum<P: AsRef<Path>>(path: P) -> Result<String, std::
     let result = hasher.finalize();
     
     Ok(format!("{:x}", result))
-}
\ No newline at end of file
+}
+
+/// 列出目录下所有普通文件的相对路径（不跟随符号链接）
+pub fn list_files_recursive(root: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(entry.path());
+            } else if file_type.is_file() {
+                if let Ok(relative) = entry.path().strip_prefix(root) {
+                    files.push(relative.to_path_buf());
+                }
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// 以流式方式计算文件的 SHA-256 校验和（阻塞调用）
+pub fn calculate_checksum_blocking(path: &Path) -> Result<String, std::io::Error> {
+    use sha2::{Digest, Sha256};
+
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// user.js 中由本应用维护的区块标记，区块以外的内容保持不变
+const MANAGED_BLOCK_BEGIN: &str = "// BEGIN chrome-tester managed prefs";
+const MANAGED_BLOCK_END: &str = "// END chrome-tester managed prefs";
+
+/// 自动化启动时默认写入的首选项（可被启动配置覆盖）
+pub fn default_firefox_prefs() -> BTreeMap<String, serde_json::Value> {
+    let prefs: [(&str, serde_json::Value); 9] = [
+        ("browser.shell.checkDefaultBrowser", false.into()),
+        ("browser.startup.homepage_override.mstone", "ignore".into()),
+        ("browser.aboutwelcome.enabled", false.into()),
+        ("startup.homepage_welcome_url", "about:blank".into()),
+        ("datareporting.policy.dataSubmissionEnabled", false.into()),
+        ("toolkit.telemetry.reportingpolicy.firstRun", false.into()),
+        ("app.update.disabledForTesting", true.into()),
+        ("browser.tabs.warnOnClose", false.into()),
+        ("remote.prefs.recommended", true.into()),
+    ];
+    prefs
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect()
+}
+
+/// 格式化为 `user_pref("name", value);`，Firefox 首选项只支持布尔、整数和字符串
+pub fn format_user_pref(name: &str, value: &serde_json::Value) -> Result<String, String> {
+    if name.trim().is_empty() {
+        return Err("Firefox preference name cannot be empty".to_string());
+    }
+    let literal = match value {
+        serde_json::Value::Bool(_) | serde_json::Value::String(_) => value.to_string(),
+        serde_json::Value::Number(n) if n.is_i64() => n.to_string(),
+        _ => {
+            return Err(format!(
+                "Unsupported value for Firefox preference {}: {} (expected boolean, integer or string)",
+                name, value
+            ))
+        }
+    };
+    Ok(format!(
+        "user_pref({}, {});",
+        serde_json::Value::String(name.to_string()),
+        literal
+    ))
+}
+
+/// 将首选项写入配置文件目录下的 user.js
+///
+/// Firefox 每次启动时先读取 prefs.js，再用 user.js 中的值覆盖，
+/// 因此写入 user.js 能保证首选项在浏览器自行修改 prefs.js 后仍然生效。
+pub fn write_user_js(
+    profile_dir: &Path,
+    prefs: &BTreeMap<String, serde_json::Value>,
+) -> Result<(), String> {
+    let lines = prefs
+        .iter()
+        .map(|(name, value)| format_user_pref(name, value))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    std::fs::create_dir_all(profile_dir)
+        .map_err(|e| format!("Failed to create profile directory: {}", e))?;
+    let path = profile_dir.join("user.js");
+    let existing = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    // 保留用户自己写在区块之外的首选项
+    let mut content = String::new();
+    let mut in_block = false;
+    for line in existing.lines() {
+        match line.trim() {
+            MANAGED_BLOCK_BEGIN => in_block = true,
+            MANAGED_BLOCK_END => in_block = false,
+            _ if !in_block => {
+                content.push_str(line);
+                content.push('\n');
+            }
+            _ => {}
+        }
+    }
+    content.push_str(MANAGED_BLOCK_BEGIN);
+    content.push('\n');
+    for line in lines {
+        content.push_str(&line);
+        content.push('\n');
+    }
+    content.push_str(MANAGED_BLOCK_END);
+    content.push('\n');
+
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Firefox 识别的命令行选项（不含前缀的 "-" 或 "--"）及是否带取值
+///
+/// URL 类选项的取值可以省略，省略时仍按开关处理。
+const FIREFOX_OPTIONS: &[(&str, bool)] = &[
+    ("profile", true),
+    ("P", true),
+    ("no-remote", false),
+    ("new-instance", false),
+    ("headless", false),
+    ("marionette", false),
+    ("remote-debugging-port", true),
+    ("remote-allow-hosts", true),
+    ("remote-allow-origins", true),
+    ("start-debugger-server", true),
+    ("width", true),
+    ("height", true),
+    ("window-size", true),
+    ("new-window", true),
+    ("new-tab", true),
+    ("private-window", true),
+    ("private", false),
+    ("search", true),
+    ("screenshot", true),
+    ("url", true),
+    ("kiosk", false),
+    ("safe-mode", false),
+    ("devtools", false),
+    ("jsconsole", false),
+    ("browser", false),
+    ("foreground", false),
+    ("wait-for-browser", false),
+    ("purgecaches", false),
+];
+
+/// 查找参数对应的 Firefox 选项，返回是否带取值；不是已知选项时返回 None
+fn firefox_option(arg: &str) -> Option<bool> {
+    let name = arg.strip_prefix("--").or_else(|| arg.strip_prefix('-'))?;
+    let name = name.split('=').next().unwrap_or(name);
+    FIREFOX_OPTIONS
+        .iter()
+        .find(|(option, _)| *option == name)
+        .map(|(_, takes_value)| *takes_value)
+}
+
+/// 参数是 Firefox 识别的选项，或不以 "-" 开头的位置参数（如 URL）
+pub fn is_firefox_arg(arg: &str) -> bool {
+    !arg.starts_with('-') || firefox_option(arg).is_some()
+}
+
+/// 将带取值选项的 `--name=value` 形式展开为 Firefox 接受的 `--name value`
+///
+/// Firefox 的命令行解析不识别等号连接的取值；其他参数（包括未知选项）原样保留，
+/// 避免把取值中含等号的参数拆成多余的位置参数。
+pub fn firefox_command_args(args: &[String]) -> Vec<String> {
+    let mut expanded = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg.split_once('=') {
+            Some((name, value)) if firefox_option(arg) == Some(true) => {
+                expanded.push(name.to_string());
+                expanded.push(value.to_string());
+            }
+            _ => expanded.push(arg.clone()),
+        }
+    }
+    expanded
+}
@@ -0,0 +1,224 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// YIQ 空间中两个像素颜色差的最大值
+const MAX_COLOR_DELTA: f64 = 35215.0;
+/// 计算结构相似度时使用的窗口大小
+const SSIM_WINDOW: u32 = 8;
+
+/// 8 位 RGBA 图像
+#[derive(Debug, Clone)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        Some([self.pixels[i], self.pixels[i + 1], self.pixels[i + 2], self.pixels[i + 3]])
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: [u8; 4]) {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels[i..i + 4].copy_from_slice(&color);
+    }
+}
+
+/// 图像对比结果
+#[derive(Debug, Clone)]
+pub struct ImageDiff {
+    pub width: u32,
+    pub height: u32,
+    pub size_mismatch: bool,
+    pub different_pixels: u64,
+    pub total_pixels: u64,
+    pub perceptual_difference: f64, // 0 表示完全相同，1 表示完全不同
+    pub diff_image: RgbaImage,    // 不同的像素标红，其余像素淡化为灰度
+}
+
+/// 读取 PNG 图像并转换为 RGBA
+pub fn load_png<P: AsRef<Path>>(path: P) -> Result<RgbaImage, String> {
+    let path = path.as_ref();
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open image {}: {}", path.display(), e))?;
+
+    let mut decoder = png::Decoder::new(file);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder
+        .read_info()
+        .map_err(|e| format!("Failed to decode image {}: {}", path.display(), e))?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader
+        .next_frame(&mut buffer)
+        .map_err(|e| format!("Failed to decode image {}: {}", path.display(), e))?;
+    let data = &buffer[..frame.buffer_size()];
+
+    let pixels = match frame.color_type {
+        png::ColorType::Rgba => data.to_vec(),
+        png::ColorType::Rgb => data
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => data
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Grayscale => data.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+        png::ColorType::Indexed => {
+            return Err(format!("Unsupported image color type: {}", path.display()));
+        }
+    };
+
+    Ok(RgbaImage {
+        width: frame.width,
+        height: frame.height,
+        pixels,
+    })
+}
+
+/// 以 PNG 格式保存 RGBA 图像
+pub fn save_png<P: AsRef<Path>>(path: P, image: &RgbaImage) -> Result<(), String> {
+    let path = path.as_ref();
+    let file = File::create(path)
+        .map_err(|e| format!("Failed to create image {}: {}", path.display(), e))?;
+
+    let mut encoder = png::Encoder::new(BufWriter::new(file), image.width, image.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&image.pixels))
+        .map_err(|e| format!("Failed to write image {}: {}", path.display(), e))
+}
+
+/// 逐像素对比两张图像
+///
+/// 像素差按 YIQ 色彩空间计算（与 pixelmatch 相同），tolerance 取 0~1，越大越宽松；
+/// 感知差异基于亮度的结构相似度（SSIM），尺寸不同时超出部分视为完全不同。
+pub fn diff_images(baseline: &RgbaImage, candidate: &RgbaImage, tolerance: f64) -> ImageDiff {
+    let width = baseline.width.max(candidate.width);
+    let height = baseline.height.max(candidate.height);
+    let max_delta = MAX_COLOR_DELTA * tolerance.clamp(0.0, 1.0).powi(2);
+
+    let mut diff_image = RgbaImage::new(width, height);
+    let mut different_pixels = 0u64;
+
+    for y in 0..height {
+        for x in 0..width {
+            let color = match (baseline.pixel(x, y), candidate.pixel(x, y)) {
+                (Some(a), Some(b)) if color_delta(a, b) <= max_delta => {
+                    let gray = (255.0 + (luminance(a) - 255.0) * 0.1) as u8;
+                    [gray, gray, gray, 255]
+                }
+                _ => {
+                    different_pixels += 1;
+                    [255, 0, 0, 255]
+                }
+            };
+            diff_image.set_pixel(x, y, color);
+        }
+    }
+
+    let total_pixels = width as u64 * height as u64;
+    let overlap_width = baseline.width.min(candidate.width);
+    let overlap_height = baseline.height.min(candidate.height);
+    let overlap_ratio = if total_pixels == 0 {
+        1.0
+    } else {
+        (overlap_width as u64 * overlap_height as u64) as f64 / total_pixels as f64
+    };
+    let similarity = mean_ssim(baseline, candidate, overlap_width, overlap_height) * overlap_ratio;
+
+    ImageDiff {
+        width,
+        height,
+        size_mismatch: baseline.width != candidate.width || baseline.height != candidate.height,
+        different_pixels,
+        total_pixels,
+        perceptual_difference: (1.0 - similarity).clamp(0.0, 1.0),
+        diff_image,
+    }
+}
+
+/// 重叠区域内按窗口计算的平均结构相似度
+fn mean_ssim(a: &RgbaImage, b: &RgbaImage, width: u32, height: u32) -> f64 {
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+
+    let mut total = 0.0;
+    let mut windows = 0u64;
+
+    for wy in (0..height).step_by(SSIM_WINDOW as usize) {
+        for wx in (0..width).step_by(SSIM_WINDOW as usize) {
+            let mut la = Vec::new();
+            let mut lb = Vec::new();
+            for y in wy..(wy + SSIM_WINDOW).min(height) {
+                for x in wx..(wx + SSIM_WINDOW).min(width) {
+                    la.push(luminance(a.pixel(x, y).unwrap_or_default()));
+                    lb.push(luminance(b.pixel(x, y).unwrap_or_default()));
+                }
+            }
+
+            let n = la.len() as f64;
+            let mean_a = la.iter().sum::<f64>() / n;
+            let mean_b = lb.iter().sum::<f64>() / n;
+            let (mut var_a, mut var_b, mut covariance) = (0.0, 0.0, 0.0);
+            for (va, vb) in la.iter().zip(&lb) {
+                var_a += (va - mean_a) * (va - mean_a);
+                var_b += (vb - mean_b) * (vb - mean_b);
+                covariance += (va - mean_a) * (vb - mean_b);
+            }
+            var_a /= n;
+            var_b /= n;
+            covariance /= n;
+
+            total += ((2.0 * mean_a * mean_b + C1) * (2.0 * covariance + C2))
+                / ((mean_a * mean_a + mean_b * mean_b + C1) * (var_a + var_b + C2));
+            windows += 1;
+        }
+    }
+
+    if windows == 0 {
+        return 1.0;
+    }
+    (total / windows as f64).max(0.0)
+}
+
+/// 与白色背景混合后的亮度
+fn luminance(p: [u8; 4]) -> f64 {
+    let [r, g, b] = blend_white(p);
+    r * 0.29889531 + g * 0.58662247 + b * 0.11448223
+}
+
+fn blend_white(p: [u8; 4]) -> [f64; 3] {
+    let alpha = p[3] as f64 / 255.0;
+    [0, 1, 2].map(|i| 255.0 + (p[i] as f64 - 255.0) * alpha)
+}
+
+fn color_delta(a: [u8; 4], b: [u8; 4]) -> f64 {
+    if a == b {
+        return 0.0;
+    }
+    let [r1, g1, b1] = blend_white(a);
+    let [r2, g2, b2] = blend_white(b);
+
+    let y = (r1 - r2) * 0.29889531 + (g1 - g2) * 0.58662247 + (b1 - b2) * 0.11448223;
+    let i = (r1 - r2) * 0.59597799 - (g1 - g2) * 0.27417610 - (b1 - b2) * 0.32180189;
+    let q = (r1 - r2) * 0.21147017 - (g1 - g2) * 0.52261711 + (b1 - b2) * 0.31114694;
+
+    0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
@@ -0,0 +1,141 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+
+/// 单个日志文件的最大大小
+pub const LOG_FILE_MAX_BYTES: u64 = 5 * 1024 * 1024;
+/// 每个输出流保留的日志文件数（含当前文件）
+pub const LOG_FILE_MAX_COUNT: usize = 5;
+
+/// 按大小滚动的日志文件（stdout.log、stdout.log.1、stdout.log.2 ...，编号越大越旧）
+pub struct RotatingLogWriter {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    written: u64,
+}
+
+impl RotatingLogWriter {
+    pub async fn create<P: AsRef<Path>>(path: P, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path).await?;
+        let written = file.metadata().await?.len();
+
+        Ok(Self {
+            path,
+            max_bytes,
+            max_files: max_files.max(1),
+            file,
+            written,
+        })
+    }
+
+    pub async fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        if self.written > 0 && self.written + data.len() as u64 > self.max_bytes {
+            self.rotate().await?;
+        }
+
+        self.file.write_all(data).await?;
+        self.written += data.len() as u64;
+        Ok(())
+    }
+
+    async fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush().await?;
+
+        let oldest = rotated_path(&self.path, self.max_files - 1);
+        if self.max_files > 1 && oldest.exists() {
+            tokio::fs::remove_file(&oldest).await?;
+        }
+        for index in (1..self.max_files.saturating_sub(1)).rev() {
+            let from = rotated_path(&self.path, index);
+            if from.exists() {
+                tokio::fs::rename(&from, rotated_path(&self.path, index + 1)).await?;
+            }
+        }
+        if self.max_files > 1 {
+            tokio::fs::rename(&self.path, rotated_path(&self.path, 1)).await?;
+        }
+
+        self.file = File::create(&self.path).await?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+/// 滚动后的日志文件路径（index 为 0 时即当前文件）
+pub fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        return path.to_path_buf();
+    }
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", index));
+    path.with_file_name(name)
+}
+
+/// 将输出流写入滚动日志，直到流结束
+pub async fn pipe_to_rotating_log<R: AsyncRead + Unpin>(mut reader: R, path: PathBuf) -> io::Result<()> {
+    let mut writer = RotatingLogWriter::create(&path, LOG_FILE_MAX_BYTES, LOG_FILE_MAX_COUNT).await?;
+    let mut buffer = vec![0u8; 8192];
+
+    loop {
+        let read = reader.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        writer.write(&buffer[..read]).await?;
+    }
+
+    writer.file.flush().await
+}
+
+/// 读取日志（包括已滚动的文件），指定行数时只返回最后若干行
+pub async fn tail_log_file<P: AsRef<Path>>(path: P, lines: Option<usize>) -> io::Result<String> {
+    let path = path.as_ref();
+    let mut content = String::new();
+
+    for index in (0..LOG_FILE_MAX_COUNT).rev() {
+        let file = rotated_path(path, index);
+        if file.exists() {
+            let bytes = tokio::fs::read(&file).await?;
+            content.push_str(&String::from_utf8_lossy(&bytes));
+        }
+    }
+
+    Ok(match lines {
+        Some(count) => {
+            let all: Vec<&str> = content.lines().collect();
+            all[all.len().saturating_sub(count)..].join("\n")
+        }
+        None => content,
+    })
+}
+
+/// 只保留修改时间最新的若干个子目录，名称在 exclude 中的子目录不计数也不删除
+pub async fn prune_old_dirs<P: AsRef<Path>>(root: P, keep: usize, exclude: &[String]) -> io::Result<()> {
+    let root = root.as_ref();
+    if !root.exists() {
+        return Ok(());
+    }
+
+    let mut dirs = Vec::new();
+    let mut entries = tokio::fs::read_dir(root).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        if exclude.iter().any(|name| entry.file_name() == name.as_str()) {
+            continue;
+        }
+        let metadata = entry.metadata().await?;
+        if metadata.is_dir() {
+            dirs.push((metadata.modified()?, entry.path()));
+        }
+    }
+
+    dirs.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    for (_, dir) in dirs.into_iter().skip(keep) {
+        tokio::fs::remove_dir_all(&dir).await?;
+    }
+
+    Ok(())
+}
@@ -1,9 +1,23 @@
 use std::path::{Path, PathBuf};
 
+pub mod archive_utils;
+pub mod chrome_profile;
+pub mod browser_detect;
 pub mod file_utils;
+pub mod firefox_prefs;
+pub mod image_diff;
+pub mod log_utils;
+pub mod placeholder_utils;
 pub mod platform_utils;
 
+pub use archive_utils::*;
+pub use chrome_profile::*;
+pub use browser_detect::*;
 pub use file_utils::*;
+pub use firefox_prefs::*;
+pub use image_diff::*;
+pub use log_utils::*;
+pub use placeholder_utils::*;
 pub use platform_utils::*;
 
 /// 获取应用数据目录
@@ -35,6 +49,30 @@ pub fn get_browsers_dir() -> Result<PathBuf, String> {
     Ok(app_data_dir.join("browsers"))
 }
 
+/// 获取浏览器启动日志目录
+pub fn get_launch_logs_dir() -> Result<PathBuf, String> {
+    let app_data_dir = get_app_data_dir()?;
+    Ok(app_data_dir.join("logs"))
+}
+
+/// 获取页面捕获产物目录
+pub fn get_artifacts_dir() -> Result<PathBuf, String> {
+    let app_data_dir = get_app_data_dir()?;
+    Ok(app_data_dir.join("artifacts"))
+}
+
+/// 获取已删除浏览器的回收站目录
+pub fn get_trash_dir() -> Result<PathBuf, String> {
+    let app_data_dir = get_app_data_dir()?;
+    Ok(app_data_dir.join("trash"))
+}
+
+/// 获取浏览器用户数据目录的根目录
+pub fn get_profiles_dir() -> Result<PathBuf, String> {
+    let app_data_dir = get_app_data_dir()?;
+    Ok(app_data_dir.join("profiles"))
+}
+
 /// 获取数据库路径
 pub fn get_database_path() -> Result<PathBuf, String> {
     let app_data_dir = get_app_data_dir()?;
@@ -94,6 +132,14 @@ pub fn format_duration(seconds: u64) -> String {
     }
 }
 
+/// 获取一个当前空闲的本地 TCP 端口
+pub fn find_free_port() -> Result<u16, String> {
+    std::net::TcpListener::bind(("127.0.0.1", 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .map_err(|e| format!("Failed to find a free port: {}", e))
+}
+
 /// 生成唯一ID
 pub fn generate_id() -> String {
     uuid::Uuid::new_v4().to_string()
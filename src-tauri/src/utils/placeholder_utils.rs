@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+/// 查找文本中的占位符名称（形如 "{name}"，"{{" 和 "}}" 表示字面量花括号）
+pub fn find_placeholders(text: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    scan_placeholders(text, |name| {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        None
+    });
+    names
+}
+
+/// 替换文本中的占位符，返回替换结果和未定义的占位符名称
+pub fn substitute_placeholders(
+    text: &str,
+    variables: &BTreeMap<String, String>,
+) -> (String, Vec<String>) {
+    let mut unknown = Vec::new();
+    let result = scan_placeholders(text, |name| {
+        let value = variables.get(name).cloned();
+        if value.is_none() && !unknown.iter().any(|n| n == name) {
+            unknown.push(name.to_string());
+        }
+        value
+    });
+    (result, unknown)
+}
+
+/// 占位符名称只能包含字母、数字和下划线，且不以数字开头
+pub fn is_valid_placeholder_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 扫描文本，对每个占位符调用 resolve；返回 None 时保留原样
+fn scan_placeholders<F>(text: &str, mut resolve: F) -> String
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(index) = rest.find(['{', '}']) {
+        result.push_str(&rest[..index]);
+        let tail = &rest[index..];
+
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            result.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+
+        if tail.starts_with('{') {
+            if let Some(end) = tail.find('}') {
+                let name = &tail[1..end];
+                if is_valid_placeholder_name(name) {
+                    match resolve(name) {
+                        Some(value) => result.push_str(&value),
+                        None => result.push_str(&tail[..=end]),
+                    }
+                    rest = &tail[end + 1..];
+                    continue;
+                }
+            }
+        }
+
+        result.push_str(&tail[..1]);
+        rest = &tail[1..];
+    }
+
+    result.push_str(rest);
+    result
+}
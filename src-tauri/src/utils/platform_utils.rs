@@ -1,32 +1,5 @@
 use std::path::PathBuf;
 
-/// 获取当前平台标识符
-pub fn get_platform() -> &'static str {
-    if cfg!(target_os = "windows") {
-        if cfg!(target_arch = "x86_64") {
-            "win64"
-        } else if cfg!(target_arch = "x86") {
-            "win32"
-        } else {
-            "windows"
-        }
-    } else if cfg!(target_os = "macos") {
-        if cfg!(target_arch = "aarch64") {
-            "mac_arm"
-        } else {
-            "mac_x64"
-        }
-    } else if cfg!(target_os = "linux") {
-        if cfg!(target_arch = "x86_64") {
-            "linux64"
-        } else {
-            "linux"
-        }
-    } else {
-        "unknown"
-    }
-}
-
 /// 获取当前架构
 pub fn get_arch() -> &'static str {
     std::env::consts::ARCH
@@ -61,6 +34,21 @@ pub fn get_executable_extension() -> &'static str {
     }
 }
 
+/// 获取浏览器类型对应的标识符（用于下载脚本参数和安装目录名）
+pub fn get_browser_key(browser_type: &crate::models::BrowserType) -> &'static str {
+    match browser_type {
+        crate::models::BrowserType::Chrome => "chrome",
+        crate::models::BrowserType::Chromium => "chromium",
+        crate::models::BrowserType::ChromeDriver => "chromedriver",
+        crate::models::BrowserType::ChromeHeadlessShell => "chrome-headless-shell",
+        // Firefox 各渠道由 build ID 前缀区分，共用同一个目录
+        crate::models::BrowserType::Firefox
+        | crate::models::BrowserType::FirefoxNightly
+        | crate::models::BrowserType::FirefoxDevEdition
+        | crate::models::BrowserType::FirefoxEsr => "firefox",
+    }
+}
+
 /// 获取平台特定的浏览器可执行文件名
 pub fn get_browser_executable_name(browser_type: &crate::models::BrowserType) -> &'static str {
     match browser_type {
@@ -82,7 +70,7 @@ pub fn get_browser_executable_name(browser_type: &crate::models::BrowserType) ->
                 "chromium-browser"
             }
         }
-        crate::models::BrowserType::Firefox => {
+        crate::models::BrowserType::Firefox | crate::models::BrowserType::FirefoxEsr => {
             if is_windows() {
                 "firefox.exe"
             } else if is_macos() {
@@ -91,6 +79,24 @@ pub fn get_browser_executable_name(browser_type: &crate::models::BrowserType) ->
                 "firefox"
             }
         }
+        crate::models::BrowserType::FirefoxNightly => {
+            if is_windows() {
+                "firefox.exe"
+            } else if is_macos() {
+                "Firefox Nightly.app/Contents/MacOS/firefox"
+            } else {
+                "firefox"
+            }
+        }
+        crate::models::BrowserType::FirefoxDevEdition => {
+            if is_windows() {
+                "firefox.exe"
+            } else if is_macos() {
+                "Firefox Developer Edition.app/Contents/MacOS/firefox"
+            } else {
+                "firefox"
+            }
+        }
         crate::models::BrowserType::ChromeDriver => {
             if is_windows() {
                 "chromedriver.exe"
@@ -98,6 +104,13 @@ pub fn get_browser_executable_name(browser_type: &crate::models::BrowserType) ->
                 "chromedriver"
             }
         }
+        crate::models::BrowserType::ChromeHeadlessShell => {
+            if is_windows() {
+                "chrome-headless-shell.exe"
+            } else {
+                "chrome-headless-shell"
+            }
+        }
     }
 }
 
@@ -142,7 +155,11 @@ pub fn get_default_browsers_dir() -> Result<PathBuf, String> {
 pub fn is_browser_supported(browser_type: &crate::models::BrowserType) -> bool {
     match browser_type {
         crate::models::BrowserType::Chrome | crate::models::BrowserType::Chromium => true,
-        crate::models::BrowserType::Firefox => true,
+        crate::models::BrowserType::ChromeHeadlessShell => true,
+        crate::models::BrowserType::Firefox
+        | crate::models::BrowserType::FirefoxNightly
+        | crate::models::BrowserType::FirefoxDevEdition
+        | crate::models::BrowserType::FirefoxEsr => true,
         crate::models::BrowserType::ChromeDriver => true,
     }
 }
@@ -152,7 +169,7 @@ pub fn get_system_info() -> String {
     format!(
         "{} {} ({})",
         get_os_name(),
-        get_platform(),
+        crate::models::Platform::current().map_or("unknown", |p| p.as_str()),
         get_arch()
     )
 }
@@ -70,7 +70,9 @@ function App() {
     try {
       switch (action) {
         case 'open':
-          await openBrowser(browserId);
+          if (!await openBrowser(browserId)) {
+            break;
+          }
           addNotification({
             type: 'success',
             title: '浏览器启动成功',
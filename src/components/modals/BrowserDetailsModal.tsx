@@ -71,8 +71,12 @@ export function BrowserDetailsModal({ isOpen, onClose, browserId }: BrowserDetai
       case 'Chrome':
       case 'Chromium':
       case 'ChromeDriver':
+      case 'ChromeHeadlessShell':
         return <Chrome className="h-8 w-8 text-blue-500" />;
       case 'Firefox':
+      case 'FirefoxNightly':
+      case 'FirefoxDevEdition':
+      case 'FirefoxEsr':
         return <Chrome className="h-8 w-8 text-orange-500" />;
       default:
         return <Chrome className="h-8 w-8 text-gray-500" />;
@@ -89,6 +93,14 @@ export function BrowserDetailsModal({ isOpen, onClose, browserId }: BrowserDetai
         return 'Mozilla Firefox 浏览器';
       case 'ChromeDriver':
         return 'Chrome WebDriver 自动化工具';
+      case 'ChromeHeadlessShell':
+        return 'Chrome 无头模式专用浏览器';
+      case 'FirefoxNightly':
+        return 'Mozilla Firefox Nightly 每日构建';
+      case 'FirefoxDevEdition':
+        return 'Mozilla Firefox 开发者版';
+      case 'FirefoxEsr':
+        return 'Mozilla Firefox 长期支持版（ESR）';
       default:
         return '浏览器';
     }
@@ -96,7 +108,9 @@ export function BrowserDetailsModal({ isOpen, onClose, browserId }: BrowserDetai
 
   const handleOpenBrowser = async () => {
     try {
-      await openBrowser(browser.id);
+      if (!await openBrowser(browser.id)) {
+        return;
+      }
       addNotification({
         type: 'success',
         title: '启动成功',
@@ -3,6 +3,7 @@ import { Download, Chrome } from 'lucide-react';
 import { Modal, Button, Input } from '../ui';
 import { useDownloadStore, useUIStore } from '../../stores';
 import { invoke } from '@tauri-apps/api/core';
+import type { Platform, SystemInfo } from '../../types';
 
 interface DownloadModalProps {
   isOpen: boolean;
@@ -11,7 +12,9 @@ interface DownloadModalProps {
 
 export function DownloadModal({ isOpen, onClose }: DownloadModalProps) {
   const [customVersion, setCustomVersion] = useState('');
-  const [currentPlatform, setCurrentPlatform] = useState('');
+  const [hostPlatform, setHostPlatform] = useState<Platform | null>(null);
+  const [supportedPlatforms, setSupportedPlatforms] = useState<Platform[]>([]);
+  const [currentPlatform, setCurrentPlatform] = useState<Platform | ''>('');
   const [isLoadingPlatform, setIsLoadingPlatform] = useState(true);
   
   const startDownload = useDownloadStore(state => state.startDownload);
@@ -28,8 +31,10 @@ export function DownloadModal({ isOpen, onClose }: DownloadModalProps) {
   const loadSystemInfo = async () => {
     setIsLoadingPlatform(true);
     try {
-      const systemInfo = await invoke<{platform: string, arch: string}>('get_system_info');
-      setCurrentPlatform(systemInfo.platform);
+      const systemInfo = await invoke<SystemInfo>('get_system_info');
+      setHostPlatform(systemInfo.platform);
+      setSupportedPlatforms(systemInfo.supported_platforms);
+      setCurrentPlatform(systemInfo.platform ?? '');
     } catch (error) {
       console.error('Failed to load system info:', error);
       addNotification({
@@ -55,13 +60,27 @@ export function DownloadModal({ isOpen, onClose }: DownloadModalProps) {
     }
 
     try {
-      await startDownload('Chrome', version, currentPlatform);
-      
-      addNotification({
-        type: 'success',
-        title: '下载开始',
-        message: `正在下载 Google Chrome ${version}`
-      });
+      const outcome = await startDownload('Chrome', version, currentPlatform);
+
+      if ('Installed' in outcome) {
+        addNotification({
+          type: 'info',
+          title: '已安装',
+          message: `Google Chrome ${version} 已经安装，无需重复下载`
+        });
+      } else if ('InProgress' in outcome) {
+        addNotification({
+          type: 'info',
+          title: '正在下载',
+          message: `Google Chrome ${version} 已在下载中`
+        });
+      } else {
+        addNotification({
+          type: 'success',
+          title: '下载开始',
+          message: `正在下载 Google Chrome ${version}`
+        });
+      }
 
       // 关闭模态框
       handleClose();
@@ -86,13 +105,16 @@ export function DownloadModal({ isOpen, onClose }: DownloadModalProps) {
     const platformLabels: Record<string, string> = {
       'win64': 'Windows 64位',
       'win32': 'Windows 32位',
-      'mac_x64': 'macOS Intel',
-      'mac_arm': 'macOS Apple Silicon',
+      'mac-x64': 'macOS Intel',
+      'mac-arm64': 'macOS Apple Silicon',
       'linux64': 'Linux 64位',
+      'linux-arm64': 'Linux ARM64',
     };
     return platformLabels[platform] || platform;
   };
 
+  const isForeignPlatform = currentPlatform !== '' && currentPlatform !== hostPlatform;
+
   return (
     <Modal 
       isOpen={isOpen} 
@@ -114,6 +136,31 @@ export function DownloadModal({ isOpen, onClose }: DownloadModalProps) {
           </div>
         </div>
 
+        {/* 目标平台 */}
+        <div>
+          <label className="block text-sm font-medium text-gray-700 mb-3">
+            目标平台
+          </label>
+          <select
+            value={currentPlatform}
+            onChange={(e) => setCurrentPlatform(e.target.value as Platform)}
+            className="w-full rounded-md border border-gray-300 px-3 py-2 text-sm"
+            disabled={isLoadingPlatform}
+          >
+            {currentPlatform === '' && <option value="">请选择平台</option>}
+            {supportedPlatforms.map(platform => (
+              <option key={platform} value={platform}>
+                {getPlatformLabel(platform)}{platform === hostPlatform ? '（本机）' : ''}
+              </option>
+            ))}
+          </select>
+          {isForeignPlatform && (
+            <p className="mt-2 text-xs text-amber-600">
+              其他平台的构建只能下载和导出安装包，不能在本机启动
+            </p>
+          )}
+        </div>
+
         {/* 版本输入 */}
         <div>
           <label className="block text-sm font-medium text-gray-700 mb-3">
@@ -106,9 +106,10 @@ export const useAppStore = create<AppStore>()(
           // Set default system info if fetch fails
           set((state) => {
             state.systemInfo = {
-              platform: 'unknown',
+              platform: null,
               arch: 'unknown',
-              available_versions: []
+              available_versions: [],
+              supported_platforms: []
             };
           });
         }
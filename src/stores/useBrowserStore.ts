@@ -1,6 +1,6 @@
 import { create } from 'zustand';
 import { immer } from 'zustand/middleware/immer';
-import type { BrowserInfo } from '../types';
+import type { BrowserInfo, SecurityValidation } from '../types';
 import { invoke } from '@tauri-apps/api/core';
 
 interface BrowserState {
@@ -14,7 +14,7 @@ interface BrowserActions {
   addBrowser: (browser: BrowserInfo) => void;
   updateBrowser: (id: string, updates: Partial<BrowserInfo>) => void;
   deleteBrowser: (id: string) => Promise<void>;
-  openBrowser: (id: string, args?: string[]) => Promise<void>;
+  openBrowser: (id: string, args?: string[]) => Promise<boolean>;
   clearAllBrowsers: () => Promise<void>;
   clearError: () => void;
   setBrowsers: (browsers: BrowserInfo[]) => void;
@@ -92,7 +92,27 @@ export const useBrowserStore = create<BrowserStore>()(
       });
 
       try {
-        await invoke('open_browser', { browserId: id, args });
+        // 启动前检查参数风险：被策略禁止时直接报错，需要确认时由用户决定是否继续
+        const validation = await invoke<SecurityValidation>('check_launch_security', { browserId: id, args });
+        if (validation.blocked) {
+          throw new Error(`启动参数被安全策略禁止：${validation.warnings.join('；')}`);
+        }
+        if (validation.requires_acknowledgement) {
+          const { confirm } = await import('@tauri-apps/plugin-dialog');
+          const confirmed = await confirm(
+            `启动参数存在以下安全风险：\n\n${validation.warnings.map(w => `• ${w}`).join('\n')}\n\n确认仍要启动吗？`,
+            { title: '确认启动风险', kind: 'warning' }
+          );
+          if (!confirmed) {
+            return false;
+          }
+        }
+
+        await invoke('open_browser', {
+          browserId: id,
+          args,
+          acknowledgeRisks: validation.requires_acknowledgement,
+        });
         
         // Update browser running status
         set((state) => {
@@ -101,6 +121,7 @@ export const useBrowserStore = create<BrowserStore>()(
             browser.is_running = true;
           }
         });
+        return true;
       } catch (error) {
         set((state) => {
           state.error = error instanceof Error ? error.message : 'Failed to open browser';
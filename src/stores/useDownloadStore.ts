@@ -1,6 +1,6 @@
 import { create } from 'zustand';
 import { immer } from 'zustand/middleware/immer';
-import type { DownloadTask, BrowserType } from '../types';
+import type { DownloadTask, BrowserType, InstallOutcome, Platform } from '../types';
 import { invoke } from '@tauri-apps/api/core';
 
 interface DownloadState {
@@ -10,7 +10,7 @@ interface DownloadState {
 }
 
 interface DownloadActions {
-  startDownload: (browserType: BrowserType, version: string, platform?: string) => Promise<string>;
+  startDownload: (browserType: BrowserType, version: string, platform?: Platform) => Promise<InstallOutcome>;
   retryDownload: (taskId: string) => Promise<void>;
   removeDownloadTask: (taskId: string) => Promise<void>;
   updateTaskProgress: (taskId: string, progress: number, downloadedBytes: number, totalBytes: number) => void;
@@ -36,15 +36,22 @@ export const useDownloadStore = create<DownloadStore>()(
         state.error = null;
       });
 
-      let taskId: string;
-
       try {
-        taskId = await invoke<string>('download_browser', {
+        const outcome = await invoke<InstallOutcome>('download_browser', {
           browserType: browserType.toLowerCase(),
           version,
           platform: platform || 'win64' // 默认平台
         });
 
+        // 已安装或已有下载任务时不创建新的任务记录
+        if (!('Started' in outcome)) {
+          set((state) => {
+            state.isLoading = false;
+          });
+          return outcome;
+        }
+        const taskId = outcome.Started.task_id;
+
         // 立即创建前端下载任务记录
         set((state) => {
           state.isLoading = false;
@@ -75,7 +82,7 @@ export const useDownloadStore = create<DownloadStore>()(
           state.downloadTasks.push(newTask);
         });
 
-        return taskId;
+        return outcome;
       } catch (error) {
         set((state) => {
           state.error = error instanceof Error ? error.message : 'Failed to start download';
@@ -3,15 +3,28 @@ export interface BrowserInfo {
   id: string;
   browser_type: BrowserType;
   version: string;
-  platform: string;
+  platform: Platform;
   install_path: string;
   executable_path: string;
   download_date: string;
   file_size: number;
   is_running: boolean;
+  is_external: boolean;
+  last_launched?: string;
 }
 
-export type BrowserType = 'Chrome' | 'Chromium' | 'Firefox' | 'ChromeDriver';
+export type BrowserType =
+  | 'Chrome'
+  | 'Chromium'
+  | 'Firefox'
+  | 'ChromeDriver'
+  | 'ChromeHeadlessShell'
+  | 'FirefoxNightly'
+  | 'FirefoxDevEdition'
+  | 'FirefoxEsr';
+
+// 构建的目标平台，其他平台的构建只能下载和导出，不能启动
+export type Platform = 'linux64' | 'linux-arm64' | 'mac-x64' | 'mac-arm64' | 'win32' | 'win64';
 
 // 下载相关类型
 export interface DownloadTask {
@@ -28,6 +41,12 @@ export interface DownloadTask {
 
 export type DownloadStatus = 'Pending' | 'Downloading' | 'Completed' | 'Failed' | 'Retrying';
 
+// 安装请求结果，同一构建不会重复下载
+export type InstallOutcome =
+  | { Started: { task_id: string } }
+  | { InProgress: { task_id: string } }
+  | { Installed: { browser_id: string } };
+
 export interface DownloadProgress {
   progress: number;
   downloaded_bytes: number;
@@ -37,14 +56,15 @@ export interface DownloadProgress {
 
 // 系统信息相关类型
 export interface SystemInfo {
-  platform: string;
+  platform: Platform | null;
   arch: string;
   available_versions: ChromeVersion[];
+  supported_platforms: Platform[];
 }
 
 export interface ChromeVersion {
   version: string;
-  platform: string;
+  platform: Platform;
   download_url?: string;
   file_size?: number;
   release_date?: string;
@@ -124,6 +144,29 @@ export interface DownloadModalProps extends ModalProps {
   isLoading?: boolean;
 }
 
+// 启动参数安全检查结果
+export type SecuritySeverity = 'Info' | 'Warning' | 'High' | 'Critical';
+
+export interface SecurityFinding {
+  rule_id: string;
+  flag: string;
+  severity: SecuritySeverity;
+  message: string;
+  rationale: string;
+  fix?: string | null;
+  forbidden_by_policy: boolean;
+}
+
+export interface SecurityValidation {
+  is_safe: boolean;
+  dangerous_parameter_count: number;
+  warnings: string[];
+  findings: SecurityFinding[];
+  highest_severity?: SecuritySeverity | null;
+  blocked: boolean;
+  requires_acknowledgement: boolean;
+}
+
 // 错误类型
 export interface AppError {
   code: string;